@@ -0,0 +1,539 @@
+//! Builder generation behind `#[derive(Builder)]`, operating on
+//! `proc_macro2` tokens so that it can be unit tested and embedded in other
+//! derive macros.
+//!
+//! [`expand`] produces the full expansion of the derive. Macro authors who
+//! want to emit a builder as part of their own derive can instead go through
+//! [`Builder`], which exposes the parsed fields and options and generates each
+//! piece of the expansion separately.
+
+use proc_macro2::TokenStream;
+use quote::{quote, format_ident, ToTokens};
+use syn::{
+    Attribute,
+    DeriveInput,
+    Data,
+    Error,
+    Fields,
+    Field,
+    Generics,
+    Lit,
+    LitStr,
+    Ident,
+    Meta,
+    NestedMeta,
+    Path,
+    PathArguments,
+    GenericArgument,
+    Result,
+    Type,
+    Visibility,
+};
+
+/// A named field of the struct being built.
+#[derive(Debug)]
+pub struct BuilderData<'ast> {
+    pub ident: &'ast Option<Ident>,
+    pub ty: &'ast Type,
+    pub is_optional: bool,
+    pub attributes: Vec<BuilderAttribute>,
+}
+
+/// An attribute given through `#[builder(...)]` on a field.
+#[derive(Debug)]
+pub enum BuilderAttribute {
+    Each(Ident),
+    /// `setter(into)`: setters accept anything convertible into the field.
+    SetterInto,
+}
+
+/// Options set by `#[builder(...)]` on the struct itself.
+#[derive(Debug)]
+pub struct BuilderOptions {
+    pub build_fn_name: Ident,
+    pub build_fn_vis: Visibility,
+    /// Name of the associated fn returning a new builder, or `None` when it
+    /// should not be generated at all.
+    pub constructor: Option<Ident>,
+    /// Prefix of the generated getters, or `None` when no getters are
+    /// requested.
+    pub getter_prefix: Option<String>,
+}
+
+impl Default for BuilderOptions {
+    fn default() -> Self {
+        BuilderOptions {
+            build_fn_name: format_ident!("build"),
+            build_fn_vis: syn::parse_quote!(pub),
+            constructor: Some(format_ident!("builder")),
+            getter_prefix: None,
+        }
+    }
+}
+
+/// Everything needed to generate the builder of one struct.
+///
+/// Its `ToTokens` impl emits the same code as [`expand`]; the individual
+/// methods emit a single item each, for callers that only want some of them.
+#[derive(Debug)]
+pub struct Builder<'ast> {
+    /// The struct being built.
+    pub name: &'ast Ident,
+    /// The generated builder struct, `{name}Builder` by default.
+    pub builder_name: Ident,
+    /// Generics of the struct being built, shared by the builder so that it
+    /// can hold borrowed data.
+    pub generics: &'ast Generics,
+    pub options: BuilderOptions,
+    pub fields: Vec<BuilderData<'ast>>,
+}
+
+/// Expands `#[derive(Builder)]` on `input`.
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    Ok(Builder::from_derive_input(input)?.into_token_stream())
+}
+
+impl<'ast> Builder<'ast> {
+    /// Parses the fields and `#[builder(...)]` attributes of a struct.
+    pub fn from_derive_input(input: &'ast DeriveInput) -> Result<Self> {
+        let name = &input.ident;
+
+        let builder_name = format_ident!("{}Builder", name);
+
+        let options = parse_builder_options(&input.attrs)?;
+
+        let mut fields = vec![];
+
+        match input.data {
+            Data::Struct(ref obj) => {
+                match obj.fields {
+                    Fields::Named(ref named) => {
+                        for field in &named.named {
+                            fields.push(get_builder_data(field)?);
+                        }
+                    }
+                    _ => return Err(Error::new_spanned(&obj.fields, "Builder requires named fields")),
+                }
+            },
+            _ => return Err(Error::new_spanned(input, "Builder can only be derived for structs")),
+        };
+
+        Ok(Builder {
+            name,
+            builder_name,
+            generics: &input.generics,
+            options,
+            fields,
+        })
+    }
+
+    /// The builder struct itself.
+    pub fn definition(&self) -> TokenStream {
+        builder_def(self)
+    }
+
+    /// The associated fn on the built struct returning a new builder, if one
+    /// is wanted.
+    pub fn constructor(&self) -> Option<TokenStream> {
+        let name = self.name;
+        let builder_name = &self.builder_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        self.options.constructor.as_ref().map(|constructor| {
+            quote! {
+                impl #impl_generics #name #ty_generics #where_clause {
+                    pub fn #constructor() -> #builder_name #ty_generics {
+                        <#builder_name #ty_generics as ::core::default::Default>::default()
+                    }
+                }
+            }
+        })
+    }
+
+    /// The `Default` impl of the builder, leaving every field unset.
+    pub fn default_impl(&self) -> TokenStream {
+        impl_default(self)
+    }
+
+    /// The setters, getters and build method of the builder.
+    pub fn methods(&self) -> TokenStream {
+        impl_builder(self)
+    }
+}
+
+impl ToTokens for Builder<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let definition = self.definition();
+        let constructor = self.constructor();
+        let default = self.default_impl();
+        let methods = self.methods();
+
+        tokens.extend(quote! {
+            #definition
+
+            #constructor
+
+            #default
+
+            #methods
+        });
+    }
+}
+
+/// Parses the `#[builder(...)]` attributes placed on the struct.
+pub fn parse_builder_options(attrs: &[Attribute]) -> Result<BuilderOptions> {
+    let mut options = BuilderOptions::default();
+
+    for attr in attrs.iter().filter(|attr| is_builder_attr(&attr.path)) {
+        let list = match attr.parse_meta()? {
+            Meta::List(ml) => ml.nested,
+            meta => return Err(Error::new_spanned(meta, "expected `builder(...)`")),
+        };
+
+        for nested in &list {
+            match nested {
+                NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("build_fn") => {
+                    for nested in &ml.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::NameValue(mnv)) if mnv.path.is_ident("name") => {
+                                options.build_fn_name = lit_str(&mnv.lit)?.parse()?;
+                            }
+                            NestedMeta::Meta(Meta::NameValue(mnv)) if mnv.path.is_ident("vis") => {
+                                options.build_fn_vis = lit_str(&mnv.lit)?.parse()?;
+                            }
+                            _ => return Err(Error::new_spanned(
+                                nested,
+                                "expected `name = \"...\"` or `vis = \"...\"`",
+                            )),
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(mnv)) if mnv.path.is_ident("constructor") => {
+                    options.constructor = Some(lit_str(&mnv.lit)?.parse()?);
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("no_constructor") => {
+                    options.constructor = None;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("getters") => {
+                    options.getter_prefix = Some("get_".into());
+                }
+                NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("getters") => {
+                    for nested in &ml.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::NameValue(mnv)) if mnv.path.is_ident("prefix") => {
+                                let prefix = lit_str(&mnv.lit)?;
+                                if prefix.value().is_empty() {
+                                    return Err(Error::new_spanned(
+                                        prefix,
+                                        "getter prefix must not be empty, getters would clash with setters",
+                                    ));
+                                }
+                                options.getter_prefix = Some(prefix.value());
+                            }
+                            _ => return Err(Error::new_spanned(nested, "expected `prefix = \"...\"`")),
+                        }
+                    }
+                }
+                _ => return Err(Error::new_spanned(
+                    nested,
+                    "expected `build_fn(...)`, `constructor = \"...\"`, `no_constructor` or `getters`",
+                )),
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Collects what the builder needs to know about a single field.
+pub fn get_builder_data(field: &Field) -> Result<BuilderData<'_>> {
+    let mut is_optional = false;
+
+    if let Type::Path(path) = &field.ty {
+        for seg in &path.path.segments {
+            if seg.ident == "Option"{
+                is_optional = true;
+            }
+        }
+    }
+
+    let mut attributes = vec![];
+    for attr in &field.attrs {
+        attributes.append(&mut parse_builder_attr(attr)?);
+    }
+
+    Ok(BuilderData {
+        ident: &field.ident,
+        ty: &field.ty,
+        is_optional,
+        attributes,
+    })
+}
+
+/// Parses one attribute of a field, returning nothing for attributes that
+/// are not `#[builder(...)]`.
+pub fn parse_builder_attr(attr: &Attribute) -> Result<Vec<BuilderAttribute>> {
+    if !is_builder_attr(&attr.path) {
+        return Ok(vec![]);
+    }
+
+    let meta = attr.parse_meta()?;
+
+    let mut attrs = vec![];
+    if let Meta::List(ml) = &meta {
+        for nested in &ml.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(mnv)) if mnv.path.is_ident("each") => {
+                    attrs.push(BuilderAttribute::Each(lit_str(&mnv.lit)?.parse()?));
+                }
+                NestedMeta::Meta(Meta::List(setter)) if setter.path.is_ident("setter") => {
+                    for nested in &setter.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("into") => {
+                                attrs.push(BuilderAttribute::SetterInto);
+                            }
+                            _ => return Err(Error::new_spanned(&meta, "expected `builder(setter(into))`")),
+                        }
+                    }
+                }
+                _ => return Err(Error::new_spanned(&meta, "expected `builder(each = \"...\")`")),
+            }
+        }
+    } else {
+        return Err(Error::new_spanned(&meta, "expected `builder(each = \"...\")`"));
+    }
+
+    Ok(attrs)
+}
+
+fn lit_str(lit: &Lit) -> Result<&LitStr> {
+    match lit {
+        Lit::Str(s) => Ok(s),
+        _ => Err(Error::new_spanned(lit, "expected string literal")),
+    }
+}
+
+fn is_builder_attr(path: &Path) -> bool {
+    path.is_ident("builder")
+}
+
+impl BuilderData<'_> {
+    /// Name of the single-element setter given by `builder(each = "...")`.
+    pub fn each(&self) -> Option<&Ident> {
+        self.attributes.iter().filter_map(|attr| match attr {
+            BuilderAttribute::Each(each) => Some(each),
+            _ => None,
+        }).next()
+    }
+
+    /// Whether setters take `impl Into<T>` rather than `T`.
+    pub fn setter_into(&self) -> bool {
+        self.attributes.iter().any(|attr| matches!(attr, BuilderAttribute::SetterInto))
+    }
+}
+
+/// Returns `T` for a type written as `Wrapper<T>`, e.g. `Option<T>` or
+/// `Vec<T>`.
+fn inner_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(path) = ty {
+        if let PathArguments::AngleBracketed(arg) = &path.path.segments.last()?.arguments {
+            if let Some(GenericArgument::Type(typ)) = arg.args.first() {
+                return Some(typ);
+            }
+        }
+    }
+    None
+}
+
+fn builder_def(builder: &Builder) -> TokenStream {
+    let builder_name = &builder.builder_name;
+    let generics = builder.generics;
+    let where_clause = &generics.where_clause;
+    let properties = builder.fields.iter().map(builder_properties);
+
+    quote!{
+        pub struct #builder_name #generics #where_clause {
+            #(#properties),* //
+        }
+    }
+}
+
+fn builder_properties(data: &BuilderData) -> TokenStream {
+    let ty = data.ty;
+    let name = data.ident;
+    let newty = if data.is_optional || data.each().is_some() {
+        quote!{#ty}
+    } else {
+        quote!{::core::option::Option<#ty>}
+    };
+
+    quote!{#name: #newty}
+}
+
+fn impl_default(builder: &Builder) -> TokenStream {
+    let builder_name = &builder.builder_name;
+    let (impl_generics, ty_generics, where_clause) = builder.generics.split_for_impl();
+    let names = builder.fields.iter().map(|d| &d.ident);
+    let values = builder.fields.iter().map(|d| if d.each().is_some() {
+        quote!{::core::default::Default::default()}
+    } else {
+        quote!{::core::option::Option::None}
+    });
+
+    quote!{
+        impl #impl_generics ::core::default::Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                #builder_name {
+                    #(#names: #values),*
+                }
+            }
+        }
+    }
+}
+
+fn impl_builder(builder: &Builder) -> TokenStream {
+    let builder_name = &builder.builder_name;
+    let (impl_generics, ty_generics, where_clause) = builder.generics.split_for_impl();
+    let methods = builder.fields.iter().map(builder_method);
+    let getters = builder.options.getter_prefix.as_ref().map(|prefix| {
+        let getters = builder.fields.iter().map(|d| builder_getter(d, prefix));
+        quote!{#(#getters)*}
+    });
+    let build = builder_build(builder);
+    quote!{
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #(#methods)*
+            #getters
+            #build
+        }
+    }
+}
+
+/// The parameter type and the expression converting the parameter `arg` to
+/// `ty` for a setter taking a `ty`.
+fn setter_arg(data: &BuilderData, ty: &Type, arg: &Ident) -> (TokenStream, TokenStream) {
+    if data.setter_into() {
+        (quote!{impl ::core::convert::Into<#ty>}, quote!{::core::convert::Into::into(#arg)})
+    } else {
+        (quote!{#ty}, quote!{#arg})
+    }
+}
+
+fn builder_method(data: &BuilderData) -> TokenStream {
+    let name = data.ident;
+
+    if let Some(each) = data.each() {
+        let (arg_ty, value) = setter_arg(data, inner_type(data.ty).unwrap_or(data.ty), each);
+        let one = quote!{
+            pub fn #each(&mut self, #each: #arg_ty) -> &mut Self {
+                self.#name.push(#value);
+                self
+            }
+        };
+        // The single-element setter takes precedence when both share a name.
+        if name.as_ref() == Some(each) {
+            return one;
+        }
+        let ty = data.ty;
+        return quote!{
+            #one
+
+            pub fn #name(&mut self, #name: #ty) -> &mut Self {
+                self.#name = #name;
+                self
+            }
+        };
+    }
+
+    let ty = if data.is_optional {
+        inner_type(data.ty).unwrap_or(data.ty)
+    } else {
+        data.ty
+    };
+    let (arg_ty, value) = setter_arg(data, ty, name.as_ref().unwrap());
+
+    quote!{
+        pub fn #name(&mut self, #name: #arg_ty) -> &mut Self {
+            self.#name = ::core::option::Option::Some(#value);
+            self
+        }
+    }
+}
+
+fn builder_getter(data: &BuilderData, prefix: &str) -> TokenStream {
+    let name = data.ident;
+    let getter = format_ident!("{}{}", prefix, name.as_ref().unwrap());
+
+    if data.each().is_some() {
+        let ty = inner_type(data.ty).unwrap_or(data.ty);
+        return quote!{
+            pub fn #getter(&self) -> &[#ty] {
+                &self.#name
+            }
+        };
+    }
+
+    let ty = if data.is_optional {
+        inner_type(data.ty).unwrap_or(data.ty)
+    } else {
+        data.ty
+    };
+
+    quote!{
+        pub fn #getter(&self) -> ::core::option::Option<&#ty> {
+            self.#name.as_ref()
+        }
+    }
+}
+
+/// The build method checks that every required field is set, then clones the
+/// values into the built struct so that the builder can be built again. A
+/// failed build leaves the builder untouched.
+fn builder_build(builder: &Builder) -> TokenStream {
+    let name = builder.name;
+    let (_, ty_generics, _) = builder.generics.split_for_impl();
+    let verification = builder.fields.iter().map(builder_build_verification);
+    let names = builder.fields.iter().map(|d| &d.ident);
+    let extraction = builder.fields.iter().map(builder_build_extraction);
+    let build_fn_name = &builder.options.build_fn_name;
+    let build_fn_vis = &builder.options.build_fn_vis;
+
+    quote!{
+        #build_fn_vis fn #build_fn_name(&mut self) -> ::core::result::Result<#name #ty_generics, ::std::boxed::Box<dyn ::std::error::Error>> {
+            #(#verification)*
+
+            ::core::result::Result::Ok(#name {
+                #(#names: #extraction),*
+            })
+        }
+    }
+}
+
+fn builder_build_verification(data: &BuilderData) -> Option<TokenStream> {
+    if data.is_optional || data.each().is_some() {
+        return None;
+    }
+
+    let name = data.ident;
+    let message = if let Some(name) = data.ident {
+        name.to_string() + " has not been added"
+    } else {
+        "".into()
+    };
+
+    Some(quote!{
+        if ::core::option::Option::is_none(&self.#name) {
+            return ::core::result::Result::Err(#message.into());
+        }
+    })
+}
+
+fn builder_build_extraction(data: &BuilderData) -> TokenStream {
+    let name = data.ident;
+
+    if data.is_optional || data.each().is_some() {
+        quote!{::core::clone::Clone::clone(&self.#name)}
+    } else {
+        quote!{::core::clone::Clone::clone(::core::option::Option::as_ref(&self.#name).unwrap())}
+    }
+}
@@ -0,0 +1,7 @@
+#[test]
+fn expand() {
+    expandtest::run("tests/expand", |input| {
+        let input = syn::parse2(input).unwrap();
+        derive_builder_core::expand(&input).unwrap_or_else(|err| err.to_compile_error())
+    });
+}
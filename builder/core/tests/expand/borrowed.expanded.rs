@@ -0,0 +1,72 @@
+pub struct CommandBuilder<'a, T: AsRef<str> = &'static str>
+where
+    T: Clone,
+{
+    executable: ::core::option::Option<&'a str>,
+    current_dir: Option<std::borrow::Cow<'a, str>>,
+    args: Vec<T>,
+}
+impl<'a, T: AsRef<str>> Command<'a, T>
+where
+    T: Clone,
+{
+    pub fn builder() -> CommandBuilder<'a, T> {
+        <CommandBuilder<'a, T> as ::core::default::Default>::default()
+    }
+}
+impl<'a, T: AsRef<str>> ::core::default::Default for CommandBuilder<'a, T>
+where
+    T: Clone,
+{
+    fn default() -> Self {
+        CommandBuilder {
+            executable: ::core::option::Option::None,
+            current_dir: ::core::option::Option::None,
+            args: ::core::default::Default::default(),
+        }
+    }
+}
+impl<'a, T: AsRef<str>> CommandBuilder<'a, T>
+where
+    T: Clone,
+{
+    pub fn executable(&mut self, executable: &'a str) -> &mut Self {
+        self.executable = ::core::option::Option::Some(executable);
+        self
+    }
+    pub fn current_dir(
+        &mut self,
+        current_dir: impl ::core::convert::Into<std::borrow::Cow<'a, str>>,
+    ) -> &mut Self {
+        self
+            .current_dir = ::core::option::Option::Some(
+            ::core::convert::Into::into(current_dir),
+        );
+        self
+    }
+    pub fn arg(&mut self, arg: T) -> &mut Self {
+        self.args.push(arg);
+        self
+    }
+    pub fn args(&mut self, args: Vec<T>) -> &mut Self {
+        self.args = args;
+        self
+    }
+    pub fn build(
+        &mut self,
+    ) -> ::core::result::Result<
+        Command<'a, T>,
+        ::std::boxed::Box<dyn ::std::error::Error>,
+    > {
+        if ::core::option::Option::is_none(&self.executable) {
+            return ::core::result::Result::Err("executable has not been added".into());
+        }
+        ::core::result::Result::Ok(Command {
+            executable: ::core::clone::Clone::clone(
+                ::core::option::Option::as_ref(&self.executable).unwrap(),
+            ),
+            current_dir: ::core::clone::Clone::clone(&self.current_dir),
+            args: ::core::clone::Clone::clone(&self.args),
+        })
+    }
+}
@@ -0,0 +1,10 @@
+pub struct Command<'a, T: AsRef<str> = &'static str>
+where
+    T: Clone,
+{
+    executable: &'a str,
+    #[builder(setter(into))]
+    current_dir: Option<std::borrow::Cow<'a, str>>,
+    #[builder(each = "arg")]
+    args: Vec<T>,
+}
@@ -0,0 +1,58 @@
+pub struct CommandBuilder {
+    executable: ::core::option::Option<String>,
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+impl Command {
+    pub fn builder() -> CommandBuilder {
+        <CommandBuilder as ::core::default::Default>::default()
+    }
+}
+impl ::core::default::Default for CommandBuilder {
+    fn default() -> Self {
+        CommandBuilder {
+            executable: ::core::option::Option::None,
+            args: ::core::default::Default::default(),
+            env: ::core::default::Default::default(),
+            current_dir: ::core::option::Option::None,
+        }
+    }
+}
+impl CommandBuilder {
+    pub fn executable(&mut self, executable: String) -> &mut Self {
+        self.executable = ::core::option::Option::Some(executable);
+        self
+    }
+    pub fn arg(&mut self, arg: String) -> &mut Self {
+        self.args.push(arg);
+        self
+    }
+    pub fn args(&mut self, args: Vec<String>) -> &mut Self {
+        self.args = args;
+        self
+    }
+    pub fn env(&mut self, env: String) -> &mut Self {
+        self.env.push(env);
+        self
+    }
+    pub fn current_dir(&mut self, current_dir: String) -> &mut Self {
+        self.current_dir = ::core::option::Option::Some(current_dir);
+        self
+    }
+    pub fn build(
+        &mut self,
+    ) -> ::core::result::Result<Command, ::std::boxed::Box<dyn ::std::error::Error>> {
+        if ::core::option::Option::is_none(&self.executable) {
+            return ::core::result::Result::Err("executable has not been added".into());
+        }
+        ::core::result::Result::Ok(Command {
+            executable: ::core::clone::Clone::clone(
+                ::core::option::Option::as_ref(&self.executable).unwrap(),
+            ),
+            args: ::core::clone::Clone::clone(&self.args),
+            env: ::core::clone::Clone::clone(&self.env),
+            current_dir: ::core::clone::Clone::clone(&self.current_dir),
+        })
+    }
+}
@@ -0,0 +1,60 @@
+pub struct CommandBuilder {
+    executable: ::core::option::Option<String>,
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+impl Command {
+    pub fn new_builder() -> CommandBuilder {
+        <CommandBuilder as ::core::default::Default>::default()
+    }
+}
+impl ::core::default::Default for CommandBuilder {
+    fn default() -> Self {
+        CommandBuilder {
+            executable: ::core::option::Option::None,
+            args: ::core::default::Default::default(),
+            current_dir: ::core::option::Option::None,
+        }
+    }
+}
+impl CommandBuilder {
+    pub fn executable(&mut self, executable: String) -> &mut Self {
+        self.executable = ::core::option::Option::Some(executable);
+        self
+    }
+    pub fn arg(&mut self, arg: String) -> &mut Self {
+        self.args.push(arg);
+        self
+    }
+    pub fn args(&mut self, args: Vec<String>) -> &mut Self {
+        self.args = args;
+        self
+    }
+    pub fn current_dir(&mut self, current_dir: String) -> &mut Self {
+        self.current_dir = ::core::option::Option::Some(current_dir);
+        self
+    }
+    pub fn peek_executable(&self) -> ::core::option::Option<&String> {
+        self.executable.as_ref()
+    }
+    pub fn peek_args(&self) -> &[String] {
+        &self.args
+    }
+    pub fn peek_current_dir(&self) -> ::core::option::Option<&String> {
+        self.current_dir.as_ref()
+    }
+    pub(crate) fn finish(
+        &mut self,
+    ) -> ::core::result::Result<Command, ::std::boxed::Box<dyn ::std::error::Error>> {
+        if ::core::option::Option::is_none(&self.executable) {
+            return ::core::result::Result::Err("executable has not been added".into());
+        }
+        ::core::result::Result::Ok(Command {
+            executable: ::core::clone::Clone::clone(
+                ::core::option::Option::as_ref(&self.executable).unwrap(),
+            ),
+            args: ::core::clone::Clone::clone(&self.args),
+            current_dir: ::core::clone::Clone::clone(&self.current_dir),
+        })
+    }
+}
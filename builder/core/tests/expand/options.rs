@@ -0,0 +1,8 @@
+#[builder(build_fn(name = "finish", vis = "pub(crate)"), constructor = "new_builder")]
+#[builder(getters(prefix = "peek_"))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
@@ -0,0 +1,3 @@
+compile_error! {
+    "expected `builder(each = \"...\")`"
+}
@@ -0,0 +1,4 @@
+pub struct Command {
+    #[builder(eac = "arg")]
+    args: Vec<String>,
+}
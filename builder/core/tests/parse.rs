@@ -0,0 +1,90 @@
+use derive_builder_core::{
+    get_builder_data, parse_builder_attr, parse_builder_options, Builder, BuilderAttribute,
+};
+use quote::quote;
+use syn::parse::Parser;
+use syn::{parse_quote, Attribute, DeriveInput, Field};
+
+#[test]
+fn optional_field() {
+    let field = Field::parse_named.parse2(quote!(current_dir: Option<String>)).unwrap();
+    let data = get_builder_data(&field).unwrap();
+
+    assert!(data.is_optional);
+    assert!(data.attributes.is_empty());
+}
+
+#[test]
+fn each_attribute() {
+    let attr: Attribute = parse_quote!(#[builder(each = "arg")]);
+    let attrs = parse_builder_attr(&attr).unwrap();
+
+    match attrs.as_slice() {
+        [BuilderAttribute::Each(each)] => assert_eq!(each, "arg"),
+        other => panic!("unexpected attributes: {:?}", other),
+    }
+}
+
+#[test]
+fn foreign_attribute_is_ignored() {
+    let attr: Attribute = parse_quote!(#[doc = "the program to run"]);
+
+    assert!(parse_builder_attr(&attr).unwrap().is_empty());
+}
+
+#[test]
+fn unrecognized_attribute() {
+    let attr: Attribute = parse_quote!(#[builder(eac = "arg")]);
+    let err = parse_builder_attr(&attr).unwrap_err();
+
+    assert_eq!(err.to_string(), "expected `builder(each = \"...\")`");
+}
+
+#[test]
+fn struct_options() {
+    let input: DeriveInput = parse_quote! {
+        #[builder(build_fn(name = "finish", vis = "pub(crate)"), no_constructor)]
+        #[builder(getters(prefix = "peek_"))]
+        pub struct Command {}
+    };
+    let options = parse_builder_options(&input.attrs).unwrap();
+
+    assert_eq!(options.build_fn_name, "finish");
+    assert_eq!(options.build_fn_vis, parse_quote!(pub(crate)));
+    assert!(options.constructor.is_none());
+    assert_eq!(options.getter_prefix.as_deref(), Some("peek_"));
+}
+
+#[test]
+fn empty_getter_prefix() {
+    let input: DeriveInput = parse_quote! {
+        #[builder(getters(prefix = ""))]
+        pub struct Command {}
+    };
+
+    assert!(parse_builder_options(&input.attrs).is_err());
+}
+
+#[test]
+fn embed_in_other_derive() {
+    let input: DeriveInput = parse_quote! {
+        pub struct Command {
+            executable: String,
+        }
+    };
+    let mut builder = Builder::from_derive_input(&input).unwrap();
+    builder.builder_name = parse_quote!(CommandFactory);
+    builder.options.constructor = None;
+
+    let definition = builder.definition();
+    assert_eq!(
+        definition.to_string(),
+        quote! {
+            pub struct CommandFactory {
+                executable: ::core::option::Option<String>
+            }
+        }
+        .to_string(),
+    );
+    assert!(builder.constructor().is_none());
+}
@@ -0,0 +1,41 @@
+// The names of the generated entry points can be customized from an attribute
+// on the struct itself.
+//
+// `build_fn(name = "...", vis = "...")` renames the method that produces the
+// final struct and changes its visibility, while `constructor = "..."` renames
+// the associated fn on the struct that creates a new builder. The builder also
+// implements Default, which behaves the same as the constructor.
+//
+//     impl Command {
+//         pub fn new_builder() -> CommandBuilder {...}
+//     }
+//
+//     impl CommandBuilder {
+//         pub(crate) fn finish(&mut self) -> Result<Command, Box<dyn Error>> {...}
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(build_fn(name = "finish", vis = "pub(crate)"), constructor = "new_builder")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::new_builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .finish()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert!(command.current_dir.is_none());
+
+    let mut builder = CommandBuilder::default();
+    assert!(builder.finish().is_err());
+}
@@ -0,0 +1,28 @@
+// Sometimes the struct already defines its own `builder` associated fn, for
+// example to pre-populate some of the fields. In that case the macro must not
+// generate one when `#[builder(no_constructor)]` is given; the builder can
+// still be obtained through its Default impl.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(no_constructor)]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+impl Command {
+    pub fn builder() -> CommandBuilder {
+        let mut builder = CommandBuilder::default();
+        builder.executable("cargo".to_owned());
+        builder
+    }
+}
+
+fn main() {
+    let command = Command::builder().build().unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert!(command.current_dir.is_none());
+}
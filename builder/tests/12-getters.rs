@@ -0,0 +1,54 @@
+// When a builder is filled in across several functions it is convenient to be
+// able to read back what has been set so far. With `#[builder(getters)]` the
+// builder gets one accessor per field, named with a `get_` prefix so that it
+// does not clash with the setter of the same field. The prefix can be changed
+// with `#[builder(getters(prefix = "..."))]`.
+//
+// Getters return `Option<&T>` for ordinary and optional fields, and a slice of
+// the elements collected so far for fields using `each`.
+//
+//     impl CommandBuilder {
+//         pub fn get_executable(&self) -> Option<&String> {...}
+//         pub fn get_args(&self) -> &[String] {...}
+//         pub fn get_current_dir(&self) -> Option<&String> {...}
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(getters)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(getters(prefix = "peek_"))]
+pub struct Env {
+    key: String,
+}
+
+fn add_release(builder: &mut CommandBuilder) {
+    if builder.get_executable().map(String::as_str) == Some("cargo") {
+        builder.arg("--release".to_owned());
+    }
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    assert!(builder.get_executable().is_none());
+    assert!(builder.get_args().is_empty());
+
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+    add_release(&mut builder);
+
+    assert_eq!(builder.get_executable().unwrap(), "cargo");
+    assert_eq!(builder.get_args(), ["build", "--release"]);
+    assert!(builder.get_current_dir().is_none());
+
+    let mut env = Env::builder();
+    env.key("PATH".to_owned());
+    assert_eq!(env.peek_key().unwrap(), "PATH");
+}
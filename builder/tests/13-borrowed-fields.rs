@@ -0,0 +1,51 @@
+// Structs holding borrowed data get a builder with the same lifetime
+// parameters, so the builder can hold the borrowed values too. The build
+// method clones the values out of the builder, which for a `&'a str` or a
+// borrowed `Cow<'a, str>` copies only the reference.
+//
+// With `#[builder(setter(into))]` on a field, the setter accepts anything that
+// converts into the field type. For a `Cow<'a, str>` this means both `&'a str`
+// and `String` can be passed.
+//
+//     impl<'a> CommandBuilder<'a> {
+//         pub fn executable(&mut self, executable: &'a str) -> &mut Self {...}
+//         pub fn current_dir(&mut self, current_dir: impl Into<Cow<'a, str>>) -> &mut Self {...}
+//         pub fn build(&mut self) -> Result<Command<'a>, Box<dyn Error>> {...}
+//     }
+
+use derive_builder::Builder;
+use std::borrow::Cow;
+
+#[derive(Builder)]
+pub struct Command<'a> {
+    executable: &'a str,
+    #[builder(each = "arg", setter(into))]
+    args: Vec<Cow<'a, str>>,
+    #[builder(setter(into))]
+    current_dir: Option<Cow<'a, str>>,
+}
+
+fn main() {
+    let executable = String::from("cargo");
+
+    let command = Command::builder()
+        .executable(&executable)
+        .arg("build")
+        .arg(format!("--jobs={}", 4))
+        .current_dir("..")
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert!(matches!(command.args[0], Cow::Borrowed("build")));
+    assert!(matches!(command.args[1], Cow::Owned(_)));
+    assert!(matches!(command.current_dir, Some(Cow::Borrowed(".."))));
+
+    let command = Command::builder()
+        .executable("rustc")
+        .current_dir(String::from("/tmp"))
+        .build()
+        .unwrap();
+
+    assert!(matches!(command.current_dir, Some(Cow::Owned(_))));
+}
@@ -0,0 +1,53 @@
+// The build method clones the values out of the builder, so one builder can
+// build several structs. This holds for every field, including those holding
+// a `Cow`, whether required, optional or set one element at a time.
+
+use derive_builder::Builder;
+use std::borrow::Cow;
+
+#[derive(Builder)]
+pub struct Command<'a> {
+    executable: &'a str,
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct Label<'a> {
+    text: Cow<'a, str>,
+    color: Option<Cow<'a, str>>,
+    #[builder(each = "tag")]
+    tags: Vec<Cow<'a, str>>,
+    width: u32,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder
+        .executable("cargo")
+        .args(vec!["build".to_owned()])
+        .current_dir("..".to_owned());
+
+    let first = builder.build().unwrap();
+    let second = builder.build().unwrap();
+    assert_eq!(first.executable, second.executable);
+    assert_eq!(first.args, second.args);
+    assert_eq!(second.current_dir.as_deref(), Some(".."));
+
+    let mut builder = Label::builder();
+    builder
+        .text(Cow::Owned("hello".to_owned()))
+        .color(Cow::Borrowed("red"))
+        .tag(Cow::Borrowed("x"))
+        .width(4);
+
+    let first = builder.build().unwrap();
+    let second = builder.build().unwrap();
+    for label in [&first, &second] {
+        assert_eq!(label.text, "hello");
+        assert_eq!(label.color.as_deref(), Some("red"));
+        assert_eq!(label.tags, ["x"]);
+        assert_eq!(label.width, 4);
+    }
+    assert!(matches!(second.color, Some(Cow::Borrowed("red"))));
+}
@@ -10,4 +10,9 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     //t.compile_fail("tests/08-unrecognized-attribute.rs");
     //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-custom-build-fn.rs");
+    t.pass("tests/11-no-constructor.rs");
+    t.pass("tests/12-getters.rs");
+    t.pass("tests/13-borrowed-fields.rs");
+    t.pass("tests/14-build-twice.rs");
 }
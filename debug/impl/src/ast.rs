@@ -0,0 +1,228 @@
+//! Shape of the type a derive is applied to, with its `#[debug(...)]`
+//! attributes already parsed.
+
+use crate::attr;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data as SynData, DeriveInput, Error, Fields, Generics, Index, Member, Meta, NestedMeta,
+    Result, Token, Type,
+};
+
+pub struct Input<'a> {
+    pub ident: &'a Ident,
+    pub generics: &'a Generics,
+    pub attrs: attr::Container,
+    pub data: Data<'a>,
+    /// Whether the type is `#[repr(packed)]`, so that its fields may not be
+    /// borrowed.
+    pub packed: bool,
+}
+
+pub enum Data<'a> {
+    Struct(Style, Vec<Field<'a>>),
+    Enum(Vec<Variant<'a>>),
+    Union(&'a Token![union], Vec<Field<'a>>),
+}
+
+pub struct Variant<'a> {
+    pub ident: &'a Ident,
+    pub attrs: attr::Variant,
+    pub style: Style,
+    pub fields: Vec<Field<'a>>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Style {
+    /// Named fields.
+    Struct,
+    /// Unnamed fields.
+    Tuple,
+    /// No fields.
+    Unit,
+}
+
+pub struct Field<'a> {
+    pub member: Member,
+    pub ty: &'a Type,
+    pub attrs: attr::Field,
+    pub original: &'a syn::Field,
+}
+
+impl<'a> Input<'a> {
+    pub fn from_syn(input: &'a DeriveInput) -> Result<Self> {
+        let attrs = attr::Container::from_attrs(&input.attrs)?;
+
+        let data = match &input.data {
+            SynData::Struct(data) => {
+                let (style, fields) = fields_from_syn(&data.fields, &attrs)?;
+                Data::Struct(style, fields)
+            }
+            SynData::Enum(data) => {
+                let variants = data
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let (style, fields) = fields_from_syn(&variant.fields, &attrs)?;
+                        Ok(Variant {
+                            ident: &variant.ident,
+                            attrs: attr::Variant::from_attrs(&variant.attrs)?,
+                            style,
+                            fields,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Data::Enum(variants)
+            }
+            SynData::Union(data) => {
+                let fields = data
+                    .fields
+                    .named
+                    .iter()
+                    .map(|field| {
+                        Ok(Field {
+                            member: Member::Named(field.ident.clone().unwrap()),
+                            ty: &field.ty,
+                            attrs: field_attrs(field, &attrs)?,
+                            original: field,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Data::Union(&data.union_token, fields)
+            }
+        };
+
+        Ok(Input {
+            ident: &input.ident,
+            generics: &input.generics,
+            attrs,
+            data,
+            packed: is_packed(&input.attrs)?,
+        })
+    }
+
+    /// Every field of the type, across all variants of an enum.
+    pub fn fields(&self) -> Box<dyn Iterator<Item = &Field<'a>> + '_> {
+        match &self.data {
+            Data::Struct(_, fields) => Box::new(fields.iter()),
+            Data::Enum(variants) => Box::new(variants.iter().flat_map(|v| v.fields.iter())),
+            Data::Union(_, fields) => Box::new(fields.iter()),
+        }
+    }
+}
+
+impl Field<'_> {
+    /// Name of the local variable the field is bound to when destructured.
+    pub fn binding(&self) -> Ident {
+        self.binding_as("field")
+    }
+
+    /// Like `binding`, for code destructuring several values at once, each
+    /// with its own `prefix`.
+    pub fn binding_as(&self, prefix: &str) -> Ident {
+        match &self.member {
+            Member::Named(ident) => format_ident!("__{}_{}", prefix, ident),
+            Member::Unnamed(index) => format_ident!("__{}_{}", prefix, index.index),
+        }
+    }
+}
+
+/// Pattern destructuring a struct or variant at `path`, binding each field
+/// by reference. Fields for which `bind` returns false are matched by `_`.
+pub fn pattern<F>(path: &TokenStream, style: Style, fields: &[Field], bind: F) -> TokenStream
+where
+    F: Fn(&Field) -> bool,
+{
+    pattern_as(path, style, fields, |field| bind(field).then(|| field.binding()))
+}
+
+/// Like `pattern`, binding each field to the name returned by `bind`.
+pub fn pattern_as<F>(path: &TokenStream, style: Style, fields: &[Field], bind: F) -> TokenStream
+where
+    F: Fn(&Field) -> Option<Ident>,
+{
+    let members = fields.iter().map(|field| &field.member);
+    let bindings = fields.iter().map(|field| match bind(field) {
+        Some(binding) => quote!(#binding),
+        None => quote!(_),
+    });
+    match style {
+        Style::Struct => quote!(#path { #(#members: #bindings),* }),
+        Style::Tuple => quote!(#path(#(#bindings),*)),
+        Style::Unit => quote!(#path),
+    }
+}
+
+/// Parses the attributes of a field, applying those of the container that
+/// affect every field.
+fn field_attrs(field: &syn::Field, container: &attr::Container) -> Result<attr::Field> {
+    let mut attrs = attr::Field::from_attrs(&field.attrs)?;
+    if container.deterministic && is_hash_collection(&field.ty) && !attrs.prints_custom() {
+        attrs.sorted = true;
+    }
+    Ok(attrs)
+}
+
+/// Whether `ty` is, or is a reference to, a `HashMap` or `HashSet`, whose
+/// iteration order differs between runs.
+fn is_hash_collection(mut ty: &Type) -> bool {
+    while let Type::Reference(reference) = ty {
+        ty = &reference.elem;
+    }
+    match ty {
+        Type::Path(ty) => ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "HashMap" || segment.ident == "HashSet"),
+        _ => false,
+    }
+}
+
+fn is_packed(attrs: &[Attribute]) -> Result<bool> {
+    for attr in attrs {
+        if !attr.path.is_ident("repr") {
+            continue;
+        }
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("packed") => return Ok(true),
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("packed") => return Ok(true),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn fields_from_syn<'a>(fields: &'a Fields, container: &attr::Container) -> Result<(Style, Vec<Field<'a>>)> {
+    let style = match fields {
+        Fields::Named(_) => Style::Struct,
+        Fields::Unnamed(_) => Style::Tuple,
+        Fields::Unit => Style::Unit,
+    };
+
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let attrs = field_attrs(field, container)?;
+            if field.ident.is_none() && attrs.rename.is_some() {
+                return Err(Error::new_spanned(field, "tuple fields are printed without a name to rename"));
+            }
+            Ok(Field {
+                member: match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                },
+                ty: &field.ty,
+                attrs,
+                original: field,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok((style, fields))
+}
@@ -0,0 +1,390 @@
+//! Parsing of the `#[debug ...]`, `#[display ...]` and `#[cmp ...]`
+//! attributes.
+
+use crate::case::RenameRule;
+use proc_macro2::Span;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Error, ExprPath, Lit, LitStr, Meta, NestedMeta, Path, Result, Token, WherePredicate,
+};
+
+/// Attributes on the struct or enum itself.
+#[derive(Default)]
+pub struct Container {
+    /// `#[debug(bound = "...")]`, replacing all inferred bounds.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// `#[display("...")]` on a struct.
+    pub display: Option<LitStr>,
+    /// `#[display(bound = "...")]`, replacing the bounds inferred for Display.
+    pub display_bound: Option<Vec<WherePredicate>>,
+    /// `#[debug(rename = "...")]`, the name printed for the type.
+    pub rename: Option<String>,
+    /// `#[debug(rename_all = "...")]`, applied to the fields of a struct or
+    /// the variants of an enum.
+    pub rename_all: Option<RenameRule>,
+    /// `#[debug(transparent)]`, forwarding to the only printed field.
+    pub transparent: Option<Path>,
+    /// `#[debug(cycle_safe)]`, printing `<cycle>` for a value that is
+    /// already being printed.
+    pub cycle_safe: bool,
+    /// `#[debug(depth = N)]`, printing `..` for values nested deeper than N.
+    pub depth: Option<usize>,
+    /// `#[debug(union_fmt = "...")]`, a function formatting a union.
+    pub union_fmt: Option<ExprPath>,
+    /// `#[debug(deterministic)]`, sorting every `HashMap` and `HashSet`
+    /// field as if it had `#[debug(sorted)]`.
+    pub deterministic: bool,
+    /// `#[cmp(bound = "...")]`, replacing the bounds inferred for PartialEq
+    /// and Hash.
+    pub cmp_bound: Option<Vec<WherePredicate>>,
+}
+
+/// Attributes on an enum variant.
+#[derive(Default)]
+pub struct Variant {
+    /// `#[display("...")]` on the variant.
+    pub display: Option<LitStr>,
+    /// `#[debug(rename = "...")]`, the name printed for the variant.
+    pub rename: Option<String>,
+}
+
+/// Attributes on a single field.
+#[derive(Default)]
+pub struct Field {
+    /// `#[debug = "..."]`, a format string applied to the field.
+    pub format: Option<LitStr>,
+    /// `#[debug(skip)]`, leaving the field out of the output.
+    pub skip: bool,
+    /// `#[debug(redact)]` or `#[debug(redact = "...")]`.
+    pub redact: Option<Redact>,
+    /// `#[debug(with = "...")]`, a function formatting the field.
+    pub with: Option<ExprPath>,
+    /// `#[debug(rename = "...")]`, the name printed for the field.
+    pub rename: Option<String>,
+    /// `#[debug(bound = "...")]`, replacing the bounds inferred from this
+    /// field only.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// `#[display(bound = "...")]`, the same for the Display impl.
+    pub display_bound: Option<Vec<WherePredicate>>,
+    /// `#[debug(truncate = N)]`, printing only the first N elements.
+    pub truncate: Option<usize>,
+    /// `#[debug(hex)]`, printing bytes in hex.
+    pub hex: bool,
+    /// `#[debug(sorted)]` on a map or set, printing it sorted by the Debug
+    /// output of its keys.
+    pub sorted: bool,
+    /// `#[cmp(skip)]`, leaving the field out of PartialEq and Hash only.
+    pub cmp_skip: bool,
+}
+
+/// What to print in place of a redacted field.
+pub enum Redact {
+    /// A fixed string, `[REDACTED]` unless given.
+    Placeholder(String),
+    /// `redact = "len"`: only the length of the value.
+    Len,
+    /// `redact = "hash"`: a prefix of the hash of the value.
+    Hash,
+}
+
+impl Container {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut container = Container::default();
+
+        for meta in metas(attrs, "debug")? {
+            match meta {
+                Meta::List(list) => {
+                    for nested in list.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                                container.bound = Some(parse_bound(lit_str(&nv.lit)?)?);
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                                container.rename = Some(lit_str(&nv.lit)?.value());
+                            }
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("transparent") => {
+                                container.transparent = Some(path);
+                            }
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("cycle_safe") => {
+                                container.cycle_safe = true;
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("depth") => {
+                                container.depth = Some(lit_int(&nv.lit)?);
+                            }
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("deterministic") => {
+                                container.deterministic = true;
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("union_fmt") => {
+                                container.union_fmt = Some(lit_str(&nv.lit)?.parse()?);
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename_all") => {
+                                container.rename_all = Some(RenameRule::from_lit(lit_str(&nv.lit)?)?);
+                            }
+                            other => return Err(Error::new_spanned(other, "unrecognized debug attribute")),
+                        }
+                    }
+                }
+                other => return Err(Error::new_spanned(other, "expected `debug(...)`")),
+            }
+        }
+
+        for meta in metas(attrs, "display")? {
+            for nested in display_list(meta)? {
+                match nested {
+                    NestedMeta::Lit(lit) => container.display = Some(lit_str(&lit)?.clone()),
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                        container.display_bound = Some(parse_bound(lit_str(&nv.lit)?)?);
+                    }
+                    other => return Err(Error::new_spanned(other, "unrecognized display attribute")),
+                }
+            }
+        }
+
+        for meta in metas(attrs, "cmp")? {
+            for nested in cmp_list(meta)? {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                        container.cmp_bound = Some(parse_bound(lit_str(&nv.lit)?)?);
+                    }
+                    other => return Err(Error::new_spanned(other, "unrecognized cmp attribute")),
+                }
+            }
+        }
+
+        Ok(container)
+    }
+}
+
+impl Variant {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut variant = Variant::default();
+
+        for meta in metas(attrs, "debug")? {
+            match meta {
+                Meta::List(list) => {
+                    for nested in list.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                                variant.rename = Some(lit_str(&nv.lit)?.value());
+                            }
+                            other => return Err(Error::new_spanned(other, "unrecognized debug attribute")),
+                        }
+                    }
+                }
+                other => return Err(Error::new_spanned(other, "expected `debug(...)`")),
+            }
+        }
+
+        for meta in metas(attrs, "display")? {
+            for nested in display_list(meta)? {
+                match nested {
+                    NestedMeta::Lit(lit) => variant.display = Some(lit_str(&lit)?.clone()),
+                    other => return Err(Error::new_spanned(other, "expected `display(\"...\")`")),
+                }
+            }
+        }
+
+        Ok(variant)
+    }
+}
+
+impl Field {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut field = Field::default();
+        let mut modes = Modes::default();
+
+        for meta in metas(attrs, "debug")? {
+            match meta {
+                Meta::NameValue(nv) => {
+                    modes.add(Mode::Format, nv.path.span())?;
+                    field.format = Some(lit_str(&nv.lit)?.clone());
+                }
+                Meta::List(list) => {
+                    for nested in list.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                                modes.add(Mode::Skip, path.span())?;
+                                field.skip = true;
+                            }
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("redact") => {
+                                modes.add(Mode::Redact, path.span())?;
+                                field.redact = Some(Redact::Placeholder("[REDACTED]".to_owned()));
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("redact") => {
+                                modes.add(Mode::Redact, nv.path.span())?;
+                                let value = lit_str(&nv.lit)?.value();
+                                field.redact = Some(match value.as_str() {
+                                    "len" => Redact::Len,
+                                    "hash" => Redact::Hash,
+                                    _ => Redact::Placeholder(value),
+                                });
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("with") => {
+                                modes.add(Mode::With, nv.path.span())?;
+                                field.with = Some(lit_str(&nv.lit)?.parse()?);
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                                field.rename = Some(lit_str(&nv.lit)?.value());
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                                field.bound = Some(parse_bound(lit_str(&nv.lit)?)?);
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("truncate") => {
+                                modes.add(Mode::Truncate, nv.path.span())?;
+                                field.truncate = Some(lit_int(&nv.lit)?);
+                            }
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("hex") => {
+                                modes.add(Mode::Hex, path.span())?;
+                                field.hex = true;
+                            }
+                            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("sorted") => {
+                                modes.add(Mode::Sorted, path.span())?;
+                                field.sorted = true;
+                            }
+                            other => return Err(Error::new_spanned(other, "unrecognized debug attribute")),
+                        }
+                    }
+                }
+                other => return Err(Error::new_spanned(other, "expected `debug = \"...\"` or `debug(...)`")),
+            }
+        }
+
+        for meta in metas(attrs, "display")? {
+            for nested in display_list(meta)? {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                        field.display_bound = Some(parse_bound(lit_str(&nv.lit)?)?);
+                    }
+                    other => return Err(Error::new_spanned(other, "unrecognized display attribute")),
+                }
+            }
+        }
+
+        for meta in metas(attrs, "cmp")? {
+            for nested in cmp_list(meta)? {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                        field.cmp_skip = true;
+                    }
+                    other => return Err(Error::new_spanned(other, "unrecognized cmp attribute")),
+                }
+            }
+        }
+
+        Ok(field)
+    }
+
+    /// Whether the field is printed other than through its own Debug impl,
+    /// which `#[debug(deterministic)]` leaves alone.
+    pub fn prints_custom(&self) -> bool {
+        self.format.is_some() || self.skip || self.redact.is_some() || self.with.is_some()
+    }
+
+    /// Whether PartialEq and Hash ignore the field, which they do for fields
+    /// skipped in the Debug output as well.
+    pub fn is_cmp_skipped(&self) -> bool {
+        self.skip || self.cmp_skip
+    }
+}
+
+/// An option deciding how a field is printed.
+#[derive(Copy, Clone, PartialEq)]
+enum Mode {
+    Format,
+    Skip,
+    Redact,
+    With,
+    Truncate,
+    Hex,
+    Sorted,
+}
+
+impl Mode {
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Format => "#[debug = \"...\"]",
+            Mode::Skip => "#[debug(skip)]",
+            Mode::Redact => "#[debug(redact)]",
+            Mode::With => "#[debug(with)]",
+            Mode::Truncate => "#[debug(truncate)]",
+            Mode::Hex => "#[debug(hex)]",
+            Mode::Sorted => "#[debug(sorted)]",
+        }
+    }
+
+    /// Whether the two options can apply to the same field. Only truncation
+    /// combines with the hex or sorted printing of a collection; any other
+    /// pair would leave one of them without effect.
+    fn combines_with(self, other: Mode) -> bool {
+        self == other
+            || matches!(
+                (self, other),
+                (Mode::Truncate, Mode::Hex)
+                    | (Mode::Truncate, Mode::Sorted)
+                    | (Mode::Hex, Mode::Truncate)
+                    | (Mode::Sorted, Mode::Truncate)
+            )
+    }
+}
+
+/// The printing options seen so far on a field.
+#[derive(Default)]
+struct Modes(Vec<Mode>);
+
+impl Modes {
+    fn add(&mut self, mode: Mode, span: Span) -> Result<()> {
+        if let Some(other) = self.0.iter().find(|other| !mode.combines_with(**other)) {
+            let msg = format!("{} cannot be combined with {}", mode.name(), other.name());
+            return Err(Error::new(span, msg));
+        }
+        self.0.push(mode);
+        Ok(())
+    }
+}
+
+/// Parsed contents of every `#[name ...]` attribute in `attrs`.
+fn metas(attrs: &[Attribute], name: &str) -> Result<Vec<Meta>> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(name))
+        .map(Attribute::parse_meta)
+        .collect()
+}
+
+fn display_list(meta: Meta) -> Result<Punctuated<NestedMeta, Token![,]>> {
+    match meta {
+        Meta::List(list) => Ok(list.nested),
+        other => Err(Error::new_spanned(other, "expected `display(\"...\")`")),
+    }
+}
+
+fn cmp_list(meta: Meta) -> Result<Punctuated<NestedMeta, Token![,]>> {
+    match meta {
+        Meta::List(list) => Ok(list.nested),
+        other => Err(Error::new_spanned(other, "expected `cmp(...)`")),
+    }
+}
+
+fn lit_str(lit: &Lit) -> Result<&LitStr> {
+    match lit {
+        Lit::Str(s) => Ok(s),
+        _ => Err(Error::new_spanned(lit, "expected string literal")),
+    }
+}
+
+fn lit_int(lit: &Lit) -> Result<usize> {
+    match lit {
+        Lit::Int(int) => int.base10_parse(),
+        _ => Err(Error::new_spanned(lit, "expected integer literal")),
+    }
+}
+
+/// Parses the comma separated where-predicates of a `bound = "..."`; an empty
+/// string means no bounds at all.
+fn parse_bound(lit: &LitStr) -> Result<Vec<WherePredicate>> {
+    let parser = Punctuated::<WherePredicate, Token![,]>::parse_terminated;
+    let predicates = parser.parse_str(&lit.value()).map_err(|err| Error::new(lit.span(), err))?;
+    Ok(predicates.into_iter().collect())
+}
@@ -0,0 +1,128 @@
+//! Inference of the trait bounds of a generated impl.
+//!
+//! Every type parameter that appears in a field gets bounded by the derived
+//! trait, except where it only appears inside `PhantomData`. A parameter that
+//! is only used through one of its associated types, as in `Vec<T::Value>`,
+//! bounds the associated type instead of the parameter itself.
+
+use crate::ast::{Field, Input};
+use crate::attr;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use std::collections::HashSet;
+use syn::visit::{self, Visit};
+use syn::{parse_quote, Generics, Path, Type, TypePath, WherePredicate};
+
+/// Generics of the derived impl for `input` with bounds added to the
+/// where-clause: `explicit` if the caller wrote them out through a
+/// `bound = "..."` attribute on the type, or else those inferred from `uses`.
+///
+/// `uses` pairs every field the generated code formats with the trait it is
+/// formatted through; a field may appear several times. Where `field_bound`
+/// returns the bounds written on a field, those replace the ones inferred for
+/// that field.
+pub fn with_bounds<'f, 'a: 'f, I>(
+    input: &Input,
+    explicit: Option<&Vec<WherePredicate>>,
+    field_bound: fn(&attr::Field) -> Option<&Vec<WherePredicate>>,
+    uses: I,
+) -> Generics
+where
+    I: IntoIterator<Item = (&'f Field<'a>, Path)>,
+{
+    let predicates = match explicit {
+        Some(bound) => bound.clone(),
+        None => {
+            let mut predicates = vec![];
+            for (field, trait_path) in uses {
+                match field_bound(&field.attrs) {
+                    Some(bound) => predicates.extend(bound.iter().cloned()),
+                    None => predicates.extend(infer(input.generics, field.ty, &trait_path)),
+                }
+            }
+            predicates
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    let mut seen = HashSet::new();
+    for predicate in predicates {
+        if seen.insert(quote!(#predicate).to_string()) {
+            where_clause.predicates.push(predicate);
+        }
+    }
+    generics
+}
+
+/// Bounds needed for a field of type `ty` to implement `trait_path`.
+pub fn infer(generics: &Generics, ty: &Type, trait_path: &Path) -> Vec<WherePredicate> {
+    let params = generics.type_params().map(|param| &param.ident).collect();
+    let mut visitor = TypeParamVisitor {
+        params: &params,
+        direct: vec![],
+        associated: vec![],
+    };
+    visitor.visit_type(ty);
+
+    let bounded = visitor
+        .direct
+        .into_iter()
+        .map(|param| quote!(#param))
+        .chain(visitor.associated.into_iter().map(|path| quote!(#path)));
+    bounded
+        .map(|ty: TokenStream| parse_quote!(#ty: #trait_path))
+        .collect()
+}
+
+struct TypeParamVisitor<'a> {
+    params: &'a HashSet<&'a Ident>,
+    /// Type parameters used directly, like `T` or `Vec<T>`.
+    direct: Vec<Ident>,
+    /// Associated types of type parameters, like `T::Value`.
+    associated: Vec<TypePath>,
+}
+
+impl TypeParamVisitor<'_> {
+    fn mentions_param(&self, ty: &Type) -> bool {
+        let mut visitor = TypeParamVisitor {
+            params: self.params,
+            direct: vec![],
+            associated: vec![],
+        };
+        visitor.visit_type(ty);
+        !visitor.direct.is_empty() || !visitor.associated.is_empty()
+    }
+}
+
+impl<'ast> Visit<'ast> for TypeParamVisitor<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        let segments = &ty.path.segments;
+        let last = segments.last();
+
+        // PhantomData<T> is Debug whether or not T is.
+        if last.is_some_and(|seg| seg.ident == "PhantomData") {
+            return;
+        }
+
+        if let Some(qself) = &ty.qself {
+            if self.mentions_param(&qself.ty) {
+                self.associated.push(ty.clone());
+                return;
+            }
+        } else if let Some(first) = segments.first() {
+            if self.params.contains(&first.ident) {
+                if segments.len() == 1 {
+                    if !self.direct.contains(&first.ident) {
+                        self.direct.push(first.ident.clone());
+                    }
+                } else {
+                    self.associated.push(ty.clone());
+                }
+                return;
+            }
+        }
+
+        visit::visit_type_path(self, ty);
+    }
+}
@@ -0,0 +1,184 @@
+//! Expansion of `#[derive(CustomPartialEq)]` and `#[derive(CustomHash)]`.
+//!
+//! Both leave out the fields skipped with `#[debug(skip)]` or `#[cmp(skip)]`,
+//! so that values equal according to one are also hashed the same by the
+//! other.
+
+use crate::ast::{pattern_as, Data, Field, Input, Style};
+use crate::bound;
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, DeriveInput, Error, Path, Result};
+
+pub fn expand_partial_eq(input: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+    let trait_path: Path = parse_quote!(::core::cmp::PartialEq);
+
+    let body = match &input.data {
+        Data::Struct(_, fields) if input.packed => {
+            let copies = fields.iter().filter(|field| compared(field)).map(|field| {
+                let member = &field.member;
+                let assert_copy = assert_copy(field);
+                quote! {
+                    && {
+                        #assert_copy
+                        ::core::cmp::PartialEq::eq(&{ self.#member }, &{ __other.#member })
+                    }
+                }
+            });
+            quote!(true #(#copies)*)
+        }
+        Data::Struct(style, fields) => {
+            let arm = eq_arm(&quote!(#ident), *style, fields);
+            quote! {
+                match (self, __other) {
+                    #arm
+                }
+            }
+        }
+        Data::Enum(variants) if variants.is_empty() => quote!(match *self {}),
+        Data::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let variant_ident = variant.ident;
+                eq_arm(&quote!(#ident::#variant_ident), variant.style, &variant.fields)
+            });
+            let rest = if variants.len() > 1 {
+                Some(quote!(_ => false,))
+            } else {
+                None
+            };
+            quote! {
+                match (self, __other) {
+                    #(#arms)*
+                    #rest
+                }
+            }
+        }
+        Data::Union(union_token, _) => {
+            return Err(Error::new_spanned(union_token, "CustomPartialEq does not support unions"));
+        }
+    };
+
+    let generics = with_bounds(&input, &trait_path);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::cmp::PartialEq for #ident #ty_generics #where_clause {
+            fn eq(&self, __other: &Self) -> bool {
+                #body
+            }
+        }
+    })
+}
+
+pub fn expand_hash(input: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+    let trait_path: Path = parse_quote!(::core::hash::Hash);
+
+    let body = match &input.data {
+        Data::Struct(_, fields) if input.packed => {
+            let hashes = fields.iter().filter(|field| compared(field)).map(|field| {
+                let member = &field.member;
+                let assert_copy = assert_copy(field);
+                quote! {
+                    #assert_copy
+                    ::core::hash::Hash::hash(&{ self.#member }, __state);
+                }
+            });
+            quote!(#(#hashes)*)
+        }
+        Data::Struct(style, fields) => {
+            let arm = hash_arm(&quote!(#ident), *style, fields);
+            quote! {
+                match self {
+                    #arm
+                }
+            }
+        }
+        Data::Enum(variants) if variants.is_empty() => quote!(match *self {}),
+        Data::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let variant_ident = variant.ident;
+                hash_arm(&quote!(#ident::#variant_ident), variant.style, &variant.fields)
+            });
+            quote! {
+                ::core::hash::Hash::hash(&::core::mem::discriminant(self), __state);
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(union_token, _) => {
+            return Err(Error::new_spanned(union_token, "CustomHash does not support unions"));
+        }
+    };
+
+    let generics = with_bounds(&input, &trait_path);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::hash::Hash for #ident #ty_generics #where_clause {
+            fn hash<__H: ::core::hash::Hasher>(&self, __state: &mut __H) {
+                #body
+            }
+        }
+    })
+}
+
+fn compared(field: &Field) -> bool {
+    !field.attrs.is_cmp_skipped()
+}
+
+/// Generics of the impl, with every compared field bounded by `trait_path`
+/// unless `#[cmp(bound = "...")]` says otherwise. Fields of a packed struct
+/// are compared by value, so they need to be Copy as well.
+fn with_bounds(input: &Input, trait_path: &Path) -> syn::Generics {
+    let copy: Path = parse_quote!(::core::marker::Copy);
+    let mut uses = vec![];
+    for field in input.fields().filter(|field| compared(field)) {
+        uses.push((field, trait_path.clone()));
+        if input.packed {
+            uses.push((field, copy.clone()));
+        }
+    }
+    bound::with_bounds(input, input.attrs.cmp_bound.as_ref(), |_| None, uses)
+}
+
+/// Fails to compile, at the field, unless its type is Copy.
+fn assert_copy(field: &Field) -> TokenStream {
+    let ty = field.ty;
+    quote_spanned! {ty.span()=>
+        let _: ::derive_debug::__private::AssertCopy<#ty>;
+    }
+}
+
+/// Match arm comparing two values of the struct or variant at `path`.
+fn eq_arm(path: &TokenStream, style: Style, fields: &[Field]) -> TokenStream {
+    let bind = |prefix| move |field: &Field| compared(field).then(|| field.binding_as(prefix));
+    let this = pattern_as(path, style, fields, bind("self"));
+    let other = pattern_as(path, style, fields, bind("other"));
+    let compared: Vec<&Field> = fields.iter().filter(|field| compared(field)).collect();
+    let this_bindings = compared.iter().map(|field| field.binding_as("self"));
+    let other_bindings = compared.iter().map(|field| field.binding_as("other"));
+    quote! {
+        (#this, #other) => true #(&& ::core::cmp::PartialEq::eq(#this_bindings, #other_bindings))*,
+    }
+}
+
+/// Match arm hashing the compared fields of the struct or variant at `path`.
+fn hash_arm(path: &TokenStream, style: Style, fields: &[Field]) -> TokenStream {
+    let pat = pattern_as(path, style, fields, |field| compared(field).then(|| field.binding()));
+    let bindings: Vec<Ident> = fields
+        .iter()
+        .filter(|field| compared(field))
+        .map(Field::binding)
+        .collect();
+    quote! {
+        #pat => {
+            #(::core::hash::Hash::hash(#bindings, __state);)*
+        }
+    }
+}
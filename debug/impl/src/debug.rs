@@ -0,0 +1,539 @@
+//! Expansion of `#[derive(CustomDebug)]`.
+
+use crate::ast::{pattern, Data, Field, Input, Style};
+use crate::attr::Redact;
+use crate::bound;
+use crate::case::RenameRule;
+use crate::fmt::{self, Arg, Template};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, DeriveInput, Error, GenericArgument, Member, Path, PathArguments, PathSegment, Result,
+    Type,
+};
+
+/// Fields formatted by the generated code, each with the trait it is
+/// formatted through.
+type Uses<'f, 'a> = Vec<(&'f Field<'a>, Path)>;
+
+/// Fields referred to by a format string, each with the trait it is
+/// formatted through, or `None` where it only gives a width or precision.
+type TemplateUses<'f, 'a> = Vec<(&'f Field<'a>, Option<Path>)>;
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+
+    let mut uses: Uses = vec![];
+    for fields in field_groups(&input) {
+        for field in fields.iter().filter(|field| !field.attrs.skip) {
+            match field_template(field, fields)? {
+                Some((_, template_uses)) => uses.extend(
+                    template_uses
+                        .into_iter()
+                        .filter_map(|(used, trait_path)| Some((used, trait_path?))),
+                ),
+                None => uses.extend(field_trait(field).map(|trait_path| (field, trait_path))),
+            }
+        }
+        if input.packed {
+            // Fields of a packed struct are copied out before being printed.
+            let members = bound_members(fields)?;
+            uses.extend(
+                fields
+                    .iter()
+                    .filter(|field| members.contains(&field.member))
+                    .map(|field| (field, parse_quote!(::core::marker::Copy))),
+            );
+        }
+    }
+    let generics = bound::with_bounds(
+        &input,
+        input.attrs.bound.as_ref(),
+        |attrs| attrs.bound.as_ref(),
+        uses,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    if let (Some(union_fmt), false) = (&input.attrs.union_fmt, matches!(input.data, Data::Union(..))) {
+        return Err(Error::new_spanned(
+            union_fmt,
+            "#[debug(union_fmt = \"...\")] is only supported on unions",
+        ));
+    }
+
+    let name = match &input.attrs.rename {
+        Some(rename) => rename.clone(),
+        None => ident.unraw().to_string(),
+    };
+
+    for field in input.fields() {
+        let ty = referent(field.ty);
+        if field.attrs.sorted && !is_map(ty) && !is_set(ty) {
+            return Err(Error::new_spanned(
+                field.ty,
+                "#[debug(sorted)] is only supported on maps and sets",
+            ));
+        }
+    }
+
+    let body = match &input.data {
+        Data::Struct(style, fields) if input.attrs.transparent.is_some() => {
+            let field = transparent_field(&input, fields)?;
+            let value = field_value(field, fields)?;
+            let fmt = quote!(::core::fmt::Debug::fmt(#value, __f));
+            let members = field_members(field, fields)?;
+            destructure(&input, *style, fields, |f| members.contains(&f.member), fmt)
+        }
+        _ if input.attrs.transparent.is_some() => {
+            return Err(Error::new_spanned(
+                &input.attrs.transparent,
+                "#[debug(transparent)] is only supported on structs",
+            ));
+        }
+        Data::Struct(style, fields) => {
+            let fmt = fmt_fields(&name, *style, fields, input.attrs.rename_all)?;
+            let members = bound_members(fields)?;
+            destructure(&input, *style, fields, |field| members.contains(&field.member), fmt)
+        }
+        Data::Enum(variants) if variants.is_empty() => quote!(match *self {}),
+        Data::Enum(variants) => {
+            let mut arms = vec![];
+            for variant in variants {
+                let variant_ident = variant.ident;
+                let members = bound_members(&variant.fields)?;
+                let pat = pattern(&quote!(#ident::#variant_ident), variant.style, &variant.fields, |field| {
+                    members.contains(&field.member)
+                });
+                let name = match (&variant.attrs.rename, input.attrs.rename_all) {
+                    (Some(rename), _) => rename.clone(),
+                    (None, Some(rule)) => rule.apply(&variant_ident.unraw().to_string()),
+                    (None, None) => variant_ident.unraw().to_string(),
+                };
+                let fmt = fmt_fields(&name, variant.style, &variant.fields, None)?;
+                arms.push(quote!(#pat => #fmt,));
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        // Which field of a union is initialized is not known, so unless a
+        // function is given to print it, only its size is printed.
+        Data::Union(..) => match &input.attrs.union_fmt {
+            Some(union_fmt) => quote!(#union_fmt(self, __f)),
+            None => quote! {
+                ::core::write!(__f, "{}(<{} bytes>)", #name, ::core::mem::size_of::<Self>())
+            },
+        },
+    };
+
+    let guard = cycle_guard(&input);
+    let requirements = std_requirements(&input);
+    let debug_fields = debug_fields(&input)?.map(|body| {
+        quote! {
+            impl #impl_generics ::derive_debug::DebugFields for #ident #ty_generics #where_clause {
+                fn debug_fields(&self, __visitor: &mut dyn ::derive_debug::FieldVisitor) {
+                    #body
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, __f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #guard
+                #body
+            }
+        }
+
+        #debug_fields
+
+        #(#requirements)*
+    })
+}
+
+/// Invocations failing the build with a clear error, rather than an
+/// unresolved path into `__private`, where the attributes use helpers that
+/// only exist with the `std` feature of derive_debug.
+fn std_requirements(input: &Input) -> Vec<TokenStream> {
+    let mut requirements = vec![];
+    if input.attrs.cycle_safe || input.attrs.depth.is_some() {
+        requirements.push(quote_spanned! {input.ident.span()=>
+            ::derive_debug::__private::require_std!(
+                "#[debug(cycle_safe)] and #[debug(depth = N)] require the `std` feature of derive_debug"
+            );
+        });
+    }
+    for field in input.fields().filter(|field| field.attrs.sorted) {
+        requirements.push(quote_spanned! {field.ty.span()=>
+            ::derive_debug::__private::require_std!(
+                "#[debug(sorted)] and #[debug(deterministic)] require the `std` feature of derive_debug"
+            );
+        });
+    }
+    requirements
+}
+
+/// Body of the `DebugFields` impl visiting the fields of the struct or of
+/// the current variant, or None for a union whose fields cannot be read.
+fn debug_fields(input: &Input) -> Result<Option<TokenStream>> {
+    let ident = input.ident;
+    let visit = |fields: &[Field], rename_all: Option<RenameRule>| -> Result<TokenStream> {
+        let mut visits = vec![];
+        for field in fields.iter().filter(|field| !field.attrs.skip) {
+            let name = field_name(field, rename_all);
+            let value = field_value(field, fields)?;
+            visits.push(quote!(::derive_debug::FieldVisitor::visit(__visitor, #name, #value);));
+        }
+        Ok(quote!({ #(#visits)* }))
+    };
+
+    Ok(Some(match &input.data {
+        Data::Struct(style, fields) => {
+            let visits = visit(fields, input.attrs.rename_all)?;
+            let members = bound_members(fields)?;
+            destructure(input, *style, fields, |field| members.contains(&field.member), visits)
+        }
+        Data::Enum(variants) if variants.is_empty() => quote!(match *self {}),
+        Data::Enum(variants) => {
+            let mut arms = vec![];
+            for variant in variants {
+                let variant_ident = variant.ident;
+                let members = bound_members(&variant.fields)?;
+                let pat = pattern(&quote!(#ident::#variant_ident), variant.style, &variant.fields, |field| {
+                    members.contains(&field.member)
+                });
+                let visits = visit(&variant.fields, None)?;
+                arms.push(quote!(#pat => #visits));
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(..) => return Ok(None),
+    }))
+}
+
+/// Statement registering `self` as being printed for `#[debug(cycle_safe)]`
+/// and `#[debug(depth = N)]`, returning early if it must not be printed.
+fn cycle_guard(input: &Input) -> Option<TokenStream> {
+    if !input.attrs.cycle_safe && input.attrs.depth.is_none() {
+        return None;
+    }
+
+    let detect_cycles = input.attrs.cycle_safe;
+    let max_depth = match input.attrs.depth {
+        Some(depth) => quote!(::core::option::Option::Some(#depth)),
+        None => quote!(::core::option::Option::None),
+    };
+
+    Some(quote! {
+        let _guard = match ::derive_debug::__private::cycle::enter(
+            self as *const Self as *const (),
+            ::core::any::type_name::<Self>(),
+            #detect_cycles,
+            #max_depth,
+        ) {
+            ::derive_debug::__private::cycle::Enter::Cycle => {
+                return ::core::fmt::Formatter::write_str(__f, "<cycle>");
+            }
+            ::derive_debug::__private::cycle::Enter::TooDeep => {
+                return ::core::fmt::Formatter::write_str(__f, "..");
+            }
+            ::derive_debug::__private::cycle::Enter::Entered(guard) => guard,
+        };
+    })
+}
+
+/// Binds the fields of a struct for which `bind` returns true and evaluates
+/// `fmt` with them in scope.
+///
+/// The fields of a packed struct may not be borrowed where they are, so they
+/// are copied to locals first. Their types are checked to be `Copy` at the
+/// field for a readable error.
+fn destructure<F>(input: &Input, style: Style, fields: &[Field], bind: F, fmt: TokenStream) -> TokenStream
+where
+    F: Fn(&Field) -> bool,
+{
+    if !input.packed {
+        let ident = input.ident;
+        let pat = pattern(&quote!(#ident), style, fields, bind);
+        return quote! {
+            match self {
+                #pat => #fmt,
+            }
+        };
+    }
+
+    let copies = fields.iter().filter(|field| bind(field)).map(|field| {
+        let binding = field.binding();
+        let member = &field.member;
+        let ty = field.ty;
+        let assert_copy = quote_spanned! {ty.span()=>
+            let _: ::derive_debug::__private::AssertCopy<#ty>;
+        };
+        quote! {
+            #assert_copy
+            let #binding = &{ self.#member };
+        }
+    });
+    quote! {
+        #(#copies)*
+        #fmt
+    }
+}
+
+/// The one field a `#[debug(transparent)]` struct forwards to.
+fn transparent_field<'f, 'a>(input: &Input, fields: &'f [Field<'a>]) -> Result<&'f Field<'a>> {
+    let mut printed = fields.iter().filter(|field| !field.attrs.skip);
+    let field = printed.next().ok_or_else(|| {
+        Error::new_spanned(
+            &input.attrs.transparent,
+            "#[debug(transparent)] requires a field that is not skipped",
+        )
+    })?;
+    if let Some(extra) = printed.next() {
+        return Err(Error::new_spanned(
+            extra.original,
+            "#[debug(transparent)] requires exactly one field that is not skipped",
+        ));
+    }
+    Ok(field)
+}
+
+/// Trait a field's type needs to implement to be printed.
+fn field_trait(field: &Field) -> Option<Path> {
+    if field.attrs.skip {
+        return None;
+    }
+    match field.attrs.redact {
+        Some(Redact::Hash) => Some(parse_quote!(::core::hash::Hash)),
+        Some(_) => None,
+        None if field.attrs.with.is_some() || field.attrs.hex => None,
+        None => Some(parse_quote!(::core::fmt::Debug)),
+    }
+}
+
+/// Formats the fields of a struct or variant printed as `name`, with the
+/// names of the fields converted by `rename_all` unless renamed one by one.
+fn fmt_fields(name: &str, style: Style, fields: &[Field], rename_all: Option<RenameRule>) -> Result<TokenStream> {
+    let printed: Vec<&Field> = fields.iter().filter(|field| !field.attrs.skip).collect();
+    let values = printed
+        .iter()
+        .map(|field| field_value(field, fields))
+        .collect::<Result<Vec<_>>>()?;
+    let finish = if printed.len() < fields.len() {
+        quote!(finish_non_exhaustive)
+    } else {
+        quote!(finish)
+    };
+
+    Ok(match style {
+        Style::Struct => {
+            let names = printed.iter().map(|field| field_name(field, rename_all));
+            quote! {
+                ::core::fmt::Formatter::debug_struct(__f, #name)
+                    #(.field(#names, #values))*
+                    .#finish()
+            }
+        }
+        Style::Tuple => quote! {
+            ::core::fmt::Formatter::debug_tuple(__f, #name)
+                #(.field(#values))*
+                .#finish()
+        },
+        Style::Unit => quote!(::core::fmt::Formatter::write_str(__f, #name)),
+    })
+}
+
+/// Name printed for a field, converted by `rename_all` unless renamed on the
+/// field itself. Tuple fields are named by their index.
+fn field_name(field: &Field, rename_all: Option<RenameRule>) -> String {
+    let name = match &field.member {
+        syn::Member::Named(ident) => ident.unraw().to_string(),
+        syn::Member::Unnamed(index) => index.index.to_string(),
+    };
+    match (&field.attrs.rename, rename_all) {
+        (Some(rename), _) => rename.clone(),
+        (None, Some(rule)) => rule.apply(&name),
+        (None, None) => name,
+    }
+}
+
+/// Expression of type `&dyn Debug` printing a field of the struct or variant
+/// with the given `fields`.
+fn field_value(field: &Field, fields: &[Field]) -> Result<TokenStream> {
+    let binding = field.binding();
+    if let Some(with) = &field.attrs.with {
+        return Ok(quote!(&::derive_debug::__private::DebugWith::new(#binding, #with)));
+    }
+
+    if field.attrs.hex || field.attrs.truncate.is_some() || field.attrs.sorted {
+        return Ok(collection_value(field));
+    }
+
+    if let Some((template, _)) = field_template(field, fields)? {
+        let format = &template.format;
+        let arguments = template.arguments();
+        return Ok(quote!(&::core::format_args!(#format, #arguments)));
+    }
+
+    Ok(match &field.attrs.redact {
+        Some(Redact::Placeholder(placeholder)) => {
+            quote!(&::core::format_args!("{}", #placeholder))
+        }
+        Some(Redact::Len) => quote!(&::derive_debug::__private::RedactedLen(#binding.len())),
+        Some(Redact::Hash) => quote!(&::derive_debug::__private::RedactedHash::new(#binding)),
+        None => quote!(#binding),
+    })
+}
+
+/// Template of a field printed through `#[debug = "..."]`, together with the
+/// fields it refers to. `{}` and `{0}` format the field itself, as the only
+/// positional argument, while `{name}` and `{self.1}` refer to the sibling
+/// field of that name or index.
+fn field_template<'f, 'a>(
+    field: &'f Field<'a>,
+    fields: &'f [Field<'a>],
+) -> Result<Option<(Template, TemplateUses<'f, 'a>)>> {
+    let lit = match &field.attrs.format {
+        Some(lit) => lit,
+        None => return Ok(None),
+    };
+
+    let mut uses = vec![];
+    let template = fmt::parse(lit, |arg, trait_path| {
+        let target = match arg {
+            Arg::Next | Arg::Index(0) => field,
+            Arg::Index(i) => {
+                return Err(Error::new(
+                    lit.span(),
+                    format!(
+                        "invalid reference to positional argument {}, the field itself is the only one; \
+                         refer to another tuple field as `{{self.{}}}`",
+                        i, i,
+                    ),
+                ));
+            }
+            Arg::Name(_) | Arg::Field(_) => arg
+                .find(fields)
+                .ok_or_else(|| Error::new(lit.span(), format!("no field `{}` to format", arg)))?,
+        };
+        uses.push((target, trait_path));
+        Ok(target.binding())
+    })?;
+    Ok(Some((template, uses)))
+}
+
+/// Members of the fields needed to print `field`: the field itself and the
+/// siblings its `#[debug = "..."]` template refers to.
+fn field_members(field: &Field, fields: &[Field]) -> Result<Vec<Member>> {
+    let mut members = vec![field.member.clone()];
+    if let Some((_, uses)) = field_template(field, fields)? {
+        members.extend(uses.into_iter().map(|(used, _)| used.member.clone()));
+    }
+    Ok(members)
+}
+
+/// Members of the fields needed to print a struct or variant, which may
+/// include skipped fields referred to by the template of another field.
+fn bound_members(fields: &[Field]) -> Result<Vec<Member>> {
+    let mut members = vec![];
+    for field in fields.iter().filter(|field| !field.attrs.skip) {
+        members.extend(field_members(field, fields)?);
+    }
+    Ok(members)
+}
+
+/// The fields of each struct or variant, whose templates may refer to each
+/// other. The fields of a union are never printed.
+fn field_groups<'f, 'a>(input: &'f Input<'a>) -> Vec<&'f [Field<'a>]> {
+    match &input.data {
+        Data::Struct(_, fields) => vec![fields],
+        Data::Enum(variants) => variants.iter().map(|variant| &variant.fields[..]).collect(),
+        Data::Union(..) => vec![],
+    }
+}
+
+/// Expression printing a field with `#[debug(hex)]`, `#[debug(truncate = N)]`
+/// or `#[debug(sorted)]`. The helpers work on the collection itself, so
+/// references are looked through.
+fn collection_value(field: &Field) -> TokenStream {
+    let binding = field.binding();
+    let mut ty = field.ty;
+    let mut value = quote!(#binding);
+    while let Type::Reference(reference) = ty {
+        ty = &reference.elem;
+        value = quote!(*#value);
+    }
+
+    let optional_limit = match field.attrs.truncate {
+        Some(limit) => quote!(::core::option::Option::Some(#limit)),
+        None => quote!(::core::option::Option::None),
+    };
+    let helper = if field.attrs.hex {
+        return quote!(&::derive_debug::__private::Hex(#value, #optional_limit));
+    } else if field.attrs.sorted {
+        let helper = if is_map(ty) { quote!(SortedMap) } else { quote!(SortedSet) };
+        return quote!(&::derive_debug::__private::#helper(#value, #optional_limit));
+    } else if is_string(ty) {
+        quote!(TruncateStr)
+    } else if is_map(ty) {
+        quote!(TruncateMap)
+    } else {
+        quote!(Truncate)
+    };
+    let limit = field.attrs.truncate;
+    quote!(&::derive_debug::__private::#helper(#value, #limit))
+}
+
+/// The type behind any number of references.
+fn referent(mut ty: &Type) -> &Type {
+    while let Type::Reference(reference) = ty {
+        ty = &reference.elem;
+    }
+    ty
+}
+
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+    match ty {
+        Type::Path(ty) => ty.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is written as one of the usual string types.
+fn is_string(ty: &Type) -> bool {
+    let segment = match last_segment(ty) {
+        Some(segment) => segment,
+        None => return false,
+    };
+    if segment.ident == "String" || segment.ident == "str" {
+        return true;
+    }
+    // Cow<str>, Box<str>, Rc<str>, Arc<str>
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        return args.args.iter().any(|arg| match arg {
+            GenericArgument::Type(ty) => last_segment(ty).is_some_and(|seg| seg.ident == "str"),
+            _ => false,
+        });
+    }
+    false
+}
+
+/// Whether `ty` is a set type like `HashSet` or `BTreeSet`.
+fn is_set(ty: &Type) -> bool {
+    last_segment(ty).is_some_and(|segment| segment.ident.to_string().ends_with("Set"))
+}
+
+/// Whether `ty` is a map type like `HashMap` or `BTreeMap`.
+fn is_map(ty: &Type) -> bool {
+    last_segment(ty).is_some_and(|segment| segment.ident.to_string().ends_with("Map"))
+}
@@ -0,0 +1,108 @@
+//! Expansion of `#[derive(CustomDisplay)]`.
+
+use crate::ast::{pattern, Data, Field, Input, Style};
+use crate::bound;
+use crate::fmt::{self, Arg};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Error, Ident, LitStr, Path, Result};
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+
+    let mut uses = vec![];
+    let body = match &input.data {
+        Data::Struct(style, fields) => {
+            let template = input.attrs.display.as_ref();
+            let arm = display_arm(&quote!(#ident), ident, *style, fields, template, &mut uses)?;
+            quote! {
+                match self {
+                    #arm
+                }
+            }
+        }
+        Data::Union(union_token, _) => {
+            return Err(Error::new_spanned(union_token, "CustomDisplay does not support unions"));
+        }
+        Data::Enum(variants) if variants.is_empty() => quote!(match *self {}),
+        Data::Enum(variants) => {
+            let mut arms = vec![];
+            for variant in variants {
+                let variant_ident = variant.ident;
+                arms.push(display_arm(
+                    &quote!(#ident::#variant_ident),
+                    variant_ident,
+                    variant.style,
+                    &variant.fields,
+                    variant.attrs.display.as_ref(),
+                    &mut uses,
+                )?);
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    };
+
+    let generics = bound::with_bounds(
+        &input,
+        input.attrs.display_bound.as_ref(),
+        |attrs| attrs.display_bound.as_ref(),
+        uses,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, __f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+/// Match arm writing a struct or variant according to its template, recording
+/// which fields the template formats through which trait in `uses`. Unit
+/// structs and variants without a template print their name.
+fn display_arm<'f, 'a>(
+    path: &TokenStream,
+    ident: &Ident,
+    style: Style,
+    fields: &'f [Field<'a>],
+    template: Option<&LitStr>,
+    uses: &mut Vec<(&'f Field<'a>, Path)>,
+) -> Result<TokenStream> {
+    let lit = match template {
+        Some(lit) => lit,
+        None if style == Style::Unit => {
+            let name = ident.to_string();
+            return Ok(quote!(#path => ::core::fmt::Formatter::write_str(__f, #name),));
+        }
+        None => return Err(Error::new_spanned(ident, "missing #[display(\"...\")] attribute")),
+    };
+
+    let template = fmt::parse(lit, |arg, trait_path| {
+        let field = match arg {
+            Arg::Next => {
+                return Err(Error::new(
+                    lit.span(),
+                    "placeholders must name the field they format, like `{name}` or `{0}`",
+                ));
+            }
+            Arg::Index(_) | Arg::Name(_) | Arg::Field(_) => arg
+                .find(fields)
+                .ok_or_else(|| Error::new(lit.span(), format!("no field `{}` to format", arg)))?,
+        };
+        uses.extend(trait_path.map(|trait_path| (field, trait_path)));
+        Ok(field.binding())
+    })?;
+
+    let format = &template.format;
+    let arguments = template.arguments();
+    let pat = pattern(path, style, fields, |field| template.uses(&field.binding()));
+
+    Ok(quote!(#pat => ::core::write!(__f, #format, #arguments),))
+}
@@ -0,0 +1,271 @@
+//! Format string templates whose placeholders refer to fields.
+//!
+//! A template such as `"{name} ({id:#x})"` is checked while expanding the
+//! macro and rewritten to refer to the local bindings of the fields, so that
+//! mistakes are reported against the attribute rather than as errors from
+//! rustc about the generated `format_args!`. Widths and precisions taken from
+//! an argument, as in `{:1$}` or `{:.prec$}`, are rewritten the same way.
+
+use crate::ast::Field;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use std::fmt::{self, Display};
+use syn::ext::IdentExt;
+use syn::{parse_quote, Error, LitStr, Member, Path, Result};
+
+/// What a placeholder refers to.
+pub enum Arg {
+    /// `{}` or `{:...}`.
+    Next,
+    /// `{0}`, a positional argument, which the caller decides the meaning
+    /// of.
+    Index(usize),
+    /// `{name}` or `{r#type}`, a named field.
+    Name(Ident),
+    /// `{self.0}` or `{self.name}`, a field of the struct or variant.
+    Field(Member),
+}
+
+impl Arg {
+    /// The field among `fields` that a `{0}`, `{name}` or `{self.0}`
+    /// placeholder refers to. Names match whether or not either is written as
+    /// a raw identifier.
+    pub fn find<'f, 'a>(&self, fields: &'f [Field<'a>]) -> Option<&'f Field<'a>> {
+        let member = match self {
+            Arg::Next => return None,
+            Arg::Index(i) => Member::Unnamed((*i).into()),
+            Arg::Name(name) => Member::Named(name.unraw()),
+            Arg::Field(Member::Named(name)) => Member::Named(name.unraw()),
+            Arg::Field(member) => member.clone(),
+        };
+        fields.iter().find(|field| match &field.member {
+            Member::Named(ident) => member == Member::Named(ident.unraw()),
+            Member::Unnamed(_) => field.member == member,
+        })
+    }
+}
+
+impl Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arg::Next => Ok(()),
+            Arg::Index(i) => write!(f, "{}", i),
+            Arg::Name(name) => write!(f, "{}", name),
+            Arg::Field(Member::Unnamed(index)) => write!(f, "self.{}", index.index),
+            Arg::Field(Member::Named(ident)) => write!(f, "self.{}", ident),
+        }
+    }
+}
+
+pub struct Template {
+    /// The format string with every placeholder rewritten to a named
+    /// argument.
+    pub format: LitStr,
+    /// The named arguments used by `format`, each being the binding of a
+    /// field in scope of the formatting code.
+    pub args: Vec<Ident>,
+    /// The bindings of fields giving a width or precision, which are passed
+    /// by value under the name returned by `count_name`.
+    pub counts: Vec<Ident>,
+}
+
+impl Template {
+    /// The named arguments of `format`, to follow it in `format_args!`.
+    pub fn arguments(&self) -> TokenStream {
+        let args = &self.args;
+        let count_names = self.counts.iter().map(count_name);
+        let counts = &self.counts;
+        quote!(#(#args = #args,)* #(#count_names = *#counts,)*)
+    }
+
+    /// Whether the formatting code uses the field bound to `binding`.
+    pub fn uses(&self, binding: &Ident) -> bool {
+        self.args.contains(binding) || self.counts.contains(binding)
+    }
+}
+
+/// Name under which the field bound to `binding` is passed as a width or
+/// precision, which `format_args!` needs as a `usize` rather than a reference.
+fn count_name(binding: &Ident) -> Ident {
+    format_ident!("__count{}", binding)
+}
+
+/// Parses `lit` as a format string. `resolve` is called for each placeholder
+/// with what it refers to and the formatting trait it uses, or `None` for the
+/// `usize` argument of a width or precision, and returns the binding the
+/// placeholder is rewritten to.
+pub fn parse<F>(lit: &LitStr, mut resolve: F) -> Result<Template>
+where
+    F: FnMut(&Arg, Option<Path>) -> Result<Ident>,
+{
+    let value = lit.value();
+    let mut chars = value.chars().peekable();
+    let mut format = String::new();
+    let mut args: Vec<Ident> = vec![];
+    let mut counts: Vec<Ident> = vec![];
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                format.push_str("{{");
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(Error::new(lit.span(), "unterminated `{` in format string")),
+                    }
+                }
+
+                let (arg, spec) = match placeholder.find(':') {
+                    Some(colon) => (&placeholder[..colon], Some(&placeholder[colon + 1..])),
+                    None => (placeholder.as_str(), None),
+                };
+                let arg = parse_arg(arg.trim(), lit)?;
+                let binding = resolve(&arg, Some(format_trait(spec.unwrap_or(""))))?;
+
+                format.push('{');
+                format.push_str(&binding.to_string());
+                if let Some(spec) = spec {
+                    format.push(':');
+                    format.push_str(&parse_spec(spec, lit, &mut |arg| {
+                        let binding = resolve(arg, None)?;
+                        if !counts.contains(&binding) {
+                            counts.push(binding.clone());
+                        }
+                        Ok(count_name(&binding))
+                    })?);
+                }
+                format.push('}');
+
+                if !args.contains(&binding) {
+                    args.push(binding);
+                }
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                format.push_str("}}");
+            }
+            '}' => return Err(Error::new(lit.span(), "unmatched `}` in format string")),
+            c => format.push(c),
+        }
+    }
+
+    Ok(Template {
+        format: LitStr::new(&format, lit.span()),
+        args,
+        counts,
+    })
+}
+
+fn parse_arg(arg: &str, lit: &LitStr) -> Result<Arg> {
+    if arg.is_empty() {
+        return Ok(Arg::Next);
+    }
+    if let Some(member) = arg.strip_prefix("self.") {
+        if let Ok(member) = syn::parse_str::<Member>(member) {
+            return Ok(Arg::Field(member));
+        }
+    } else if let Ok(index) = arg.parse() {
+        return Ok(Arg::Index(index));
+    } else if let Ok(name) = syn::parse_str::<Ident>(arg) {
+        return Ok(Arg::Name(name));
+    }
+    Err(Error::new(lit.span(), format!("invalid placeholder `{{{}}}`", arg)))
+}
+
+/// Rewrites the arguments a format spec takes its width or precision from,
+/// as in `>1$` or `.prec$`, to the bindings returned by `resolve`.
+fn parse_spec<F>(spec: &str, lit: &LitStr, resolve: &mut F) -> Result<String>
+where
+    F: FnMut(&Arg) -> Result<Ident>,
+{
+    let chars: Vec<char> = spec.chars().collect();
+    let is_align = |c: Option<&char>| matches!(c, Some('<') | Some('^') | Some('>'));
+    let mut i = 0;
+    if is_align(chars.get(1)) {
+        i = 2;
+    } else if is_align(chars.first()) {
+        i = 1;
+    }
+    if matches!(chars.get(i), Some('+') | Some('-')) {
+        i += 1;
+    }
+    if chars.get(i) == Some(&'#') {
+        i += 1;
+    }
+    // A `0` directly followed by `$` is the argument of the width rather
+    // than the zero-padding flag.
+    if chars.get(i) == Some(&'0') && chars.get(i + 1) != Some(&'$') {
+        i += 1;
+    }
+
+    let mut rewritten: String = chars[..i].iter().collect();
+    let (count, end) = parse_count(&chars, i, lit, resolve)?;
+    rewritten.push_str(&count);
+    i = end;
+    if chars.get(i) == Some(&'.') {
+        if chars.get(i + 1) == Some(&'*') {
+            return Err(Error::new(
+                lit.span(),
+                "`.*` is not supported, take the precision from a field with `.name$`",
+            ));
+        }
+        let (count, end) = parse_count(&chars, i + 1, lit, resolve)?;
+        rewritten.push('.');
+        rewritten.push_str(&count);
+        i = end;
+    }
+    rewritten.extend(&chars[i..]);
+    Ok(rewritten)
+}
+
+/// The width or precision starting at `chars[start]`, rewritten if it is an
+/// argument, and the index just past it. A run of characters not followed by
+/// `$` is left alone: digits are a literal count, anything else is the
+/// formatting trait.
+fn parse_count<F>(chars: &[char], start: usize, lit: &LitStr, resolve: &mut F) -> Result<(String, usize)>
+where
+    F: FnMut(&Arg) -> Result<Ident>,
+{
+    let word_end = |mut end: usize| {
+        while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+            end += 1;
+        }
+        end
+    };
+    let mut end = word_end(start);
+    // `self.1$` continues past the dot.
+    if chars[start..end] == ['s', 'e', 'l', 'f'] && chars.get(end) == Some(&'.') {
+        end = word_end(end + 1);
+    }
+    let word: String = chars[start..end].iter().collect();
+    if word.is_empty() && chars.get(end) == Some(&'$') {
+        return Err(Error::new(lit.span(), "expected an argument before `$` in format spec"));
+    }
+    if chars.get(end) == Some(&'$') {
+        let binding = resolve(&parse_arg(&word, lit)?)?;
+        Ok((format!("{}$", binding), end + 1))
+    } else if word.chars().all(|c| c.is_ascii_digit()) {
+        Ok((word, end))
+    } else {
+        Ok((String::new(), start))
+    }
+}
+
+/// The `core::fmt` trait a placeholder with the format spec `spec` uses.
+fn format_trait(spec: &str) -> Path {
+    match spec.chars().last() {
+        Some('?') => parse_quote!(::core::fmt::Debug),
+        Some('x') => parse_quote!(::core::fmt::LowerHex),
+        Some('X') => parse_quote!(::core::fmt::UpperHex),
+        Some('o') => parse_quote!(::core::fmt::Octal),
+        Some('b') => parse_quote!(::core::fmt::Binary),
+        Some('e') => parse_quote!(::core::fmt::LowerExp),
+        Some('E') => parse_quote!(::core::fmt::UpperExp),
+        _ => parse_quote!(::core::fmt::Display),
+    }
+}
@@ -0,0 +1,49 @@
+extern crate proc_macro;
+
+mod ast;
+mod attr;
+mod bound;
+mod case;
+mod cmp;
+mod debug;
+mod display;
+mod fmt;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+#[proc_macro_derive(CustomDebug, attributes(debug))]
+pub fn derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    debug::expand(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    display::expand(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(CustomPartialEq, attributes(debug, cmp))]
+pub fn derive_partial_eq(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    cmp::expand_partial_eq(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(CustomHash, attributes(debug, cmp))]
+pub fn derive_hash(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    cmp::expand_hash(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
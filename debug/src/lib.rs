@@ -1,10 +1,51 @@
-extern crate proc_macro;
+// Crates that have the "proc-macro" crate type are only allowed to export
+// procedural macros, but some of the code generated by CustomDebug needs
+// support types at runtime, for example to print a redacted field. The derive
+// lives in the derive_debug_impl crate and is re-exported from here together
+// with those types, so that users only import this one crate.
+//
+// The generated code only refers to `::core` and to this crate, so it works in
+// `#![no_std]` crates. Only the thread-local used by `#[debug(cycle_safe)]` and
+// `#[debug(depth = N)]`, and the sorting done for `#[debug(sorted)]` and
+// `#[debug(deterministic)]`, require the default `std` feature; using those
+// attributes without it is a compile error saying so.
+#![no_std]
 
-use proc_macro::TokenStream;
+#[cfg(feature = "std")]
+extern crate std;
 
-#[proc_macro_derive(CustomDebug)]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+pub use derive_debug_impl::{CustomDebug, CustomDisplay, CustomHash, CustomPartialEq};
 
-    unimplemented!()
+use core::fmt::Debug;
+
+/// Reports the fields of a value one at a time, for example to emit them as
+/// key/value pairs to a structured logger. Implemented by
+/// `#[derive(CustomDebug)]` for structs and enums.
+pub trait DebugFields {
+    /// Reports every printed field to `visitor` with its name and a value
+    /// formatted the same way as by the Debug impl.
+    fn debug_fields(&self, visitor: &mut dyn FieldVisitor);
+}
+
+/// Receives the fields of a value one at a time from
+/// `DebugFields::debug_fields`.
+///
+/// The names and values respect the same `#[debug(...)]` attributes as the
+/// Debug impl: skipped fields are not visited, renamed fields are visited
+/// under their new name and values are formatted as they would be printed.
+pub trait FieldVisitor {
+    fn visit(&mut self, name: &str, value: &dyn Debug);
 }
+
+impl<F> FieldVisitor for F
+where
+    F: FnMut(&str, &dyn Debug),
+{
+    fn visit(&mut self, name: &str, value: &dyn Debug) {
+        self(name, value)
+    }
+}
+
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
@@ -0,0 +1,338 @@
+//! Support code referenced by the generated impls. Not public API.
+
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+/// Printed in place of a `#[debug(redact = "len")]` field.
+pub struct RedactedLen(pub usize);
+
+impl Debug for RedactedLen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[REDACTED len={}]", self.0)
+    }
+}
+
+/// Printed in place of a `#[debug(redact = "hash")]` field.
+pub struct RedactedHash(u32);
+
+impl RedactedHash {
+    pub fn new<T: ?Sized + Hash>(value: &T) -> Self {
+        let mut hasher = Fnv1a::default();
+        value.hash(&mut hasher);
+        // Only a prefix, enough to tell values apart in logs.
+        RedactedHash((hasher.finish() >> 32) as u32)
+    }
+}
+
+impl Debug for RedactedHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[REDACTED hash={:08x}]", self.0)
+    }
+}
+
+/// FNV-1a, which unlike std's `DefaultHasher` is available without std and
+/// gives the same output on every run.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Prints a `#[debug(with = "...")]` field through the given function.
+pub struct DebugWith<'a, T: ?Sized, F> {
+    value: &'a T,
+    fmt: F,
+}
+
+impl<'a, T, F> DebugWith<'a, T, F>
+where
+    T: ?Sized,
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    pub fn new(value: &'a T, fmt: F) -> Self {
+        DebugWith { value, fmt }
+    }
+}
+
+impl<T, F> Debug for DebugWith<'_, T, F>
+where
+    T: ?Sized,
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.fmt)(self.value, f)
+    }
+}
+
+/// Marks the elements left out by `#[debug(truncate = N)]`.
+struct More(usize);
+
+impl Debug for More {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "... ({} more)", self.0)
+    }
+}
+
+/// Prints the first elements of a collection, for `#[debug(truncate = N)]`.
+pub struct Truncate<'a, T: ?Sized>(pub &'a T, pub usize);
+
+impl<'a, T> Debug for Truncate<'a, T>
+where
+    T: ?Sized,
+    &'a T: IntoIterator,
+    <&'a T as IntoIterator>::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut iter = self.0.into_iter();
+        let mut list = f.debug_list();
+        list.entries(iter.by_ref().take(self.1));
+        let more = iter.count();
+        if more > 0 {
+            list.entry(&More(more));
+        }
+        list.finish()
+    }
+}
+
+/// Like `Truncate`, for maps, printing them as maps.
+pub struct TruncateMap<'a, T: ?Sized>(pub &'a T, pub usize);
+
+impl<'a, T, K, V> Debug for TruncateMap<'a, T>
+where
+    T: ?Sized,
+    &'a T: IntoIterator<Item = (K, V)>,
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut iter = self.0.into_iter();
+        let mut map = f.debug_map();
+        map.entries(iter.by_ref().take(self.1));
+        let more = iter.count();
+        if more > 0 {
+            map.key(&More(more));
+            map.value(&format_args!("..."));
+        }
+        map.finish()
+    }
+}
+
+/// Like `Truncate`, for strings, counting chars.
+pub struct TruncateStr<'a, T: ?Sized>(pub &'a T, pub usize);
+
+impl<T> Debug for TruncateStr<'_, T>
+where
+    T: ?Sized + AsRef<str>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = self.0.as_ref();
+        let end = s.char_indices().nth(self.1).map_or(s.len(), |(i, _)| i);
+        Debug::fmt(&s[..end], f)?;
+        let more = s[end..].chars().count();
+        if more > 0 {
+            write!(f, "{:?}", More(more))?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints bytes in hex for `#[debug(hex)]`, optionally truncated. The
+/// alternate form puts 16 bytes on each line.
+pub struct Hex<'a, T: ?Sized>(pub &'a T, pub Option<usize>);
+
+impl<T> Debug for Hex<'_, T>
+where
+    T: ?Sized + AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = self.0.as_ref();
+        let shown = match self.1 {
+            Some(limit) if limit < bytes.len() => &bytes[..limit],
+            _ => bytes,
+        };
+
+        let row_len = if f.alternate() { 16 } else { shown.len().max(1) };
+        let mut list = f.debug_list();
+        for row in shown.chunks(row_len) {
+            list.entry(&HexRow(row));
+        }
+        if shown.len() < bytes.len() {
+            list.entry(&More(bytes.len() - shown.len()));
+        }
+        list.finish()
+    }
+}
+
+struct HexRow<'a>(&'a [u8]);
+
+impl Debug for HexRow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fails the build with `message` unless the `std` feature is enabled, for
+/// attributes whose helpers need std.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_std {
+    ($message:literal) => {};
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_std {
+    ($message:literal) => {
+        ::core::compile_error!($message);
+    };
+}
+
+pub use crate::__require_std as require_std;
+
+/// Tracking of the values being printed on the current thread, for
+/// `#[debug(cycle_safe)]` and `#[debug(depth = N)]`.
+#[cfg(feature = "std")]
+pub mod cycle {
+    use core::cell::RefCell;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static ACTIVE: RefCell<Vec<(usize, &'static str)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub enum Enter {
+        /// The value is already being printed further up the stack.
+        Cycle,
+        /// More tracked values than the depth limit are being printed.
+        TooDeep,
+        Entered(Guard),
+    }
+
+    /// Marks the value as being printed until dropped.
+    pub struct Guard(());
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            ACTIVE.with(|active| active.borrow_mut().pop());
+        }
+    }
+
+    /// Starts printing the value at `addr`. Values are identified by their
+    /// address together with their type name, because a struct and its first
+    /// field live at the same address.
+    pub fn enter(
+        addr: *const (),
+        type_name: &'static str,
+        detect_cycles: bool,
+        max_depth: Option<usize>,
+    ) -> Enter {
+        let key = (addr as usize, type_name);
+        ACTIVE.with(|active| {
+            let mut active = active.borrow_mut();
+            if detect_cycles && active.contains(&key) {
+                return Enter::Cycle;
+            }
+            if max_depth.is_some_and(|max| active.len() >= max) {
+                return Enter::TooDeep;
+            }
+            active.push(key);
+            Enter::Entered(Guard(()))
+        })
+    }
+}
+
+/// Fails to compile unless `T` is `Copy`, which fields of a packed struct
+/// need to be, to be copied out before being printed.
+pub struct AssertCopy<T: Copy>(PhantomData<T>);
+
+/// Prints a set sorted by the Debug output of its elements, for
+/// `#[debug(sorted)]`, optionally truncated.
+#[cfg(feature = "std")]
+pub struct SortedSet<'a, T: ?Sized>(pub &'a T, pub Option<usize>);
+
+#[cfg(feature = "std")]
+impl<'a, T> Debug for SortedSet<'a, T>
+where
+    T: ?Sized,
+    &'a T: IntoIterator,
+    <&'a T as IntoIterator>::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = sort_by_debug(self.0.into_iter().map(|item| (item, ())));
+        let shown = self.1.map_or(entries.len(), |limit| limit.min(entries.len()));
+        let mut set = f.debug_set();
+        set.entries(entries[..shown].iter().map(|(item, ())| item));
+        if shown < entries.len() {
+            set.entry(&More(entries.len() - shown));
+        }
+        set.finish()
+    }
+}
+
+/// Like `SortedSet`, for maps, sorting them by the Debug output of their keys.
+#[cfg(feature = "std")]
+pub struct SortedMap<'a, T: ?Sized>(pub &'a T, pub Option<usize>);
+
+#[cfg(feature = "std")]
+impl<'a, T, K, V> Debug for SortedMap<'a, T>
+where
+    T: ?Sized,
+    &'a T: IntoIterator<Item = (K, V)>,
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = sort_by_debug(self.0.into_iter());
+        let shown = self.1.map_or(entries.len(), |limit| limit.min(entries.len()));
+        let mut map = f.debug_map();
+        map.entries(entries[..shown].iter().map(|(key, value)| (key, value)));
+        if shown < entries.len() {
+            map.key(&More(entries.len() - shown));
+            map.value(&format_args!("..."));
+        }
+        map.finish()
+    }
+}
+
+/// Collects key/value pairs sorted by the Debug output of the keys. The output
+/// used for sorting is the compact one, so that the order does not depend on
+/// whether the collection is printed with `{:?}` or `{:#?}`.
+#[cfg(feature = "std")]
+fn sort_by_debug<K, V, I>(entries: I) -> std::vec::Vec<(K, V)>
+where
+    K: Debug,
+    I: Iterator<Item = (K, V)>,
+{
+    use std::string::String;
+    use std::vec::Vec;
+
+    let mut entries: Vec<(String, (K, V))> = entries
+        .map(|(key, value)| (std::format!("{:?}", key), (key, value)))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.into_iter().map(|(_, entry)| entry).collect()
+}
@@ -0,0 +1,55 @@
+// Besides structs with named fields, the derive supports tuple structs, unit
+// structs and enums. Their output matches what the standard library's
+// derive(Debug) produces: tuple-like fields go through `debug_tuple`, named
+// fields through `debug_struct`, and unit structs and variants print just
+// their name. Fields and variants named by raw identifiers print without the
+// `r#` prefix.
+//
+// Format overrides from `#[debug = "..."]` apply to fields of every kind.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+pub struct Pair(&'static str, #[debug = "0x{:02x}"] u8);
+
+#[derive(CustomDebug)]
+pub enum Shape<T> {
+    Empty,
+    Circle(#[debug = "{:.1}"] f64),
+    Rect {
+        width: T,
+        #[debug = "0b{:04b}"]
+        flags: u8,
+    },
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+#[derive(CustomDebug)]
+pub enum Token {
+    r#Loop,
+    Keyword { r#type: u8 },
+}
+
+fn main() {
+    assert_eq!(format!("{:?}", Unit), "Unit");
+    assert_eq!(format!("{:?}", Pair("p", 255)), r#"Pair("p", 0xff)"#);
+
+    assert_eq!(format!("{:?}", Shape::<u8>::Empty), "Empty");
+    assert_eq!(format!("{:?}", Shape::<u8>::Circle(1.25)), "Circle(1.2)");
+    assert_eq!(
+        format!("{:?}", Shape::Rect { width: 3, flags: 5 }),
+        "Rect { width: 3, flags: 0b0101 }",
+    );
+    assert_eq!(
+        format!("{:#?}", Shape::Rect { width: "w", flags: 1 }),
+        "Rect {\n    width: \"w\",\n    flags: 0b0001,\n}",
+    );
+
+    assert_eq!(format!("{:?}", Token::r#Loop), "Loop");
+    assert_eq!(format!("{:?}", Token::Keyword { r#type: 2 }), "Keyword { type: 2 }");
+}
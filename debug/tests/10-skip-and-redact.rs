@@ -0,0 +1,67 @@
+// Fields holding secrets or noise can be left out of the output with
+// `#[debug(skip)]`. The output then ends in `..` to show that there is more to
+// the value than what was printed, like `finish_non_exhaustive` does.
+//
+// `#[debug(redact)]` keeps the field but prints a placeholder instead of its
+// value. The placeholder can be given as a string; the special values "len"
+// and "hash" print the length of the value or a prefix of its hash, which is
+// enough to tell whether two logged secrets are the same.
+//
+// Skipped and redacted fields do not need to implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub struct NotDebug;
+
+#[derive(CustomDebug)]
+pub struct Login<T> {
+    user: &'static str,
+    #[debug(redact)]
+    password: String,
+    #[debug(redact = "***")]
+    otp: u32,
+    #[debug(redact = "len")]
+    token: Vec<u8>,
+    #[debug(redact = "hash")]
+    session: &'static str,
+    #[debug(skip)]
+    cache: T,
+}
+
+#[derive(CustomDebug)]
+pub enum Credential {
+    Password(&'static str, #[debug(skip)] NotDebug),
+    Key { id: u32 },
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let login = Login {
+        user: "ferris",
+        password: "hunter2".to_owned(),
+        otp: 123456,
+        token: vec![1, 2, 3],
+        session: "abc",
+        cache: NotDebug,
+    };
+
+    let debug = format!("{:?}", login);
+    assert!(debug.starts_with(
+        r#"Login { user: "ferris", password: [REDACTED], otp: ***, token: [REDACTED len=3], session: [REDACTED hash="#,
+    ));
+    assert!(debug.ends_with("], .. }"));
+    assert!(!debug.contains("abc"));
+
+    let again = Login { session: "abc", ..login };
+    assert_eq!(format!("{:?}", again), debug);
+
+    assert_debug::<Login<NotDebug>>();
+
+    assert_eq!(
+        format!("{:?}", Credential::Password("pw", NotDebug)),
+        r#"Password("pw", ..)"#,
+    );
+    assert_eq!(format!("{:?}", Credential::Key { id: 7 }), "Key { id: 7 }");
+}
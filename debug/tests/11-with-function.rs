@@ -0,0 +1,54 @@
+// `#[debug(with = "path::to::function")]` formats a field through a function
+// with the signature `fn(&T, &mut fmt::Formatter) -> fmt::Result`, where T is
+// the type of the field. This covers formatting that a single format string
+// cannot express, without wrapping the field in a newtype.
+//
+// The field does not need to implement Debug itself.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+use std::time::Duration;
+
+mod fmt_util {
+    use std::fmt;
+
+    pub fn hex<T: AsRef<[u8]>>(bytes: &T, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes.as_ref() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+fn millis(duration: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}ms", duration.as_millis())
+}
+
+pub struct Opaque;
+
+fn opaque(_: &Opaque, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("<opaque>")
+}
+
+#[derive(CustomDebug)]
+pub struct Request {
+    #[debug(with = "millis")]
+    timeout: Duration,
+    #[debug(with = "fmt_util::hex")]
+    body: Vec<u8>,
+    #[debug(with = "opaque")]
+    handle: Opaque,
+}
+
+fn main() {
+    let request = Request {
+        timeout: Duration::from_secs(2),
+        body: vec![0xde, 0xad, 0xbe, 0xef],
+        handle: Opaque,
+    };
+
+    let debug = format!("{:?}", request);
+    let expected = "Request { timeout: 2000ms, body: deadbeef, handle: <opaque> }";
+
+    assert_eq!(debug, expected);
+}
@@ -0,0 +1,73 @@
+// CustomDisplay derives Display from a template given in a
+// `#[display("...")]` attribute on the struct, or on each variant of an enum.
+// Placeholders refer to fields by name, or by index for tuple fields, and take
+// the same format specs as `format!`.
+//
+// Bounds are inferred the same way as for CustomDebug, except that each type
+// parameter is bounded by the trait its field is formatted through: `{id}`
+// needs Display, `{id:#x}` LowerHex and `{id:?}` Debug. Fields the template
+// does not mention need nothing.
+//
+// Unit variants without a template print their name. A field named by a raw
+// identifier is referred to the same way, as `{r#type}`.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+pub struct NotDisplay;
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{name} ({id:#x})")]
+pub struct User<T> {
+    name: String,
+    id: T,
+    #[debug(skip)]
+    marker: PhantomData<NotDisplay>,
+}
+
+#[derive(CustomDisplay)]
+pub enum Error<E> {
+    #[display("not found: {0}")]
+    NotFound(String),
+    #[display("io error {{{source:?}}} after {retries} retries")]
+    Io { source: E, retries: u8 },
+    Timeout,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}")]
+pub struct Wrapper<T>(T, NotDisplay);
+
+#[derive(CustomDisplay)]
+#[display("{r#type}: {name}")]
+pub struct Item {
+    r#type: &'static str,
+    name: &'static str,
+}
+
+fn assert_display<T: Display>() {}
+
+fn main() {
+    let user = User {
+        name: "ferris".to_owned(),
+        id: 255u32,
+        marker: PhantomData,
+    };
+    assert_eq!(user.to_string(), "ferris (0xff)");
+    assert_eq!(format!("{:?}", user), r#"User { name: "ferris", id: 255, .. }"#);
+
+    let err: Error<&str> = Error::NotFound("config".to_owned());
+    assert_eq!(err.to_string(), "not found: config");
+    let err = Error::Io { source: "eof", retries: 3 };
+    assert_eq!(err.to_string(), r#"io error {"eof"} after 3 retries"#);
+    assert_eq!(Error::<()>::Timeout.to_string(), "Timeout");
+
+    assert_eq!(Wrapper(1, NotDisplay).to_string(), "1");
+
+    let item = Item { r#type: "fruit", name: "apple" };
+    assert_eq!(item.to_string(), "fruit: apple");
+
+    // Debug is enough for E, it is only formatted with `{:?}`.
+    assert_display::<Error<Vec<u8>>>();
+}
@@ -0,0 +1,14 @@
+// Placeholders in a display template are checked against the fields of the
+// struct while the macro expands, so a typo is reported on the attribute
+// instead of as a confusing error inside generated code.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{name} ({idd})")]
+pub struct User {
+    name: String,
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+// The names in the output can differ from the Rust identifiers, for example to
+// show the domain name of a generated FFI type. `#[debug(rename = "...")]`
+// renames the type, a variant or a field. `#[debug(rename_all = "...")]` on
+// the type converts the case of every field of a struct, or every variant of
+// an enum, that is not renamed individually.
+//
+// The supported cases are "lowercase", "UPPERCASE", "PascalCase",
+// "camelCase", "snake_case", "SCREAMING_SNAKE_CASE", "kebab-case" and
+// "SCREAMING-KEBAB-CASE".
+
+use derive_debug::CustomDebug;
+
+#[allow(non_camel_case_types)]
+#[derive(CustomDebug)]
+#[debug(rename = "Request", rename_all = "camelCase")]
+pub struct ffi_request_t {
+    request_id: u32,
+    #[debug(rename = "URL")]
+    target_url: &'static str,
+    http2_enabled: bool,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Status {
+    NotFound,
+    #[debug(rename = "500")]
+    InternalError,
+    TimedOut { after_ms: u64 },
+}
+
+fn main() {
+    let request = ffi_request_t {
+        request_id: 1,
+        target_url: "/",
+        http2_enabled: true,
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Request { requestId: 1, URL: "/", http2Enabled: true }"#,
+    );
+
+    assert_eq!(format!("{:?}", Status::NotFound), "NOT_FOUND");
+    assert_eq!(format!("{:?}", Status::InternalError), "500");
+    assert_eq!(
+        format!("{:?}", Status::TimedOut { after_ms: 5 }),
+        "TIMED_OUT { after_ms: 5 }",
+    );
+}
@@ -0,0 +1,40 @@
+// For newtypes the name of the wrapper is usually noise in the output.
+// `#[debug(transparent)]` forwards straight to the Debug impl of the one
+// field, so `UserId(42)` prints as `42`.
+//
+// Skipped fields don't count, and the field's own attributes such as a format
+// string still apply.
+
+use derive_debug::CustomDebug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct UserId(u64);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Name<'a> {
+    value: &'a str,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Flags<T> {
+    #[debug = "0b{:04b}"]
+    bits: u8,
+    #[debug(skip)]
+    marker: PhantomData<T>,
+}
+
+fn main() {
+    assert_eq!(format!("{:?}", UserId(42)), "42");
+    assert_eq!(format!("{:?}", Name { value: "ferris" }), r#""ferris""#);
+    assert_eq!(format!("{:#?}", vec![UserId(1)]), "[\n    1,\n]");
+
+    let flags = Flags::<()> {
+        bits: 5,
+        marker: PhantomData,
+    };
+    assert_eq!(format!("{:?}", flags), "0b0101");
+}
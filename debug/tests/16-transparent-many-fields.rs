@@ -0,0 +1,14 @@
+// A transparent Debug impl can only forward to a single field. When there is
+// more than one field that isn't skipped, the error points at the field that
+// would be lost.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {}
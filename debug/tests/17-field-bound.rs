@@ -0,0 +1,67 @@
+// A `debug(bound = "...")` attribute on a single field substitutes only the
+// bounds that would have been inferred from that field's type, leaving the
+// bounds inferred from the other fields in place:
+//
+//     impl<T: Trait, U> Debug for Wrapper<T, U>
+//     where
+//         T::Value: Debug,
+//         U: Debug,
+//     {...}
+//
+// An empty `debug(bound = "")` opts the field out of bound inference
+// entirely, for fields whose Debug impl holds without any bounds on the type
+// parameters.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+}
+
+#[derive(CustomDebug)]
+pub struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+// Debug for any T, which inference could not know from the type alone.
+pub struct Handle<T>(Vec<T>);
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Handle(len={})", self.0.len())
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Pool<T> {
+    #[debug(bound = "")]
+    handle: Handle<T>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    struct NotDebug;
+
+    assert_debug::<Wrapper<Id, u8>>();
+    assert_debug::<Pool<NotDebug>>();
+
+    let pool = Pool {
+        handle: Handle(vec![NotDebug, NotDebug]),
+    };
+    assert_eq!(format!("{:?}", pool), "Pool { handle: Handle(len=2) }");
+}
@@ -0,0 +1,84 @@
+// Large collections make debug output, and especially the `{:#?}` form, hard
+// to read. `#[debug(truncate = N)]` prints only the first N elements of a
+// collection, or the first N chars of a string, followed by a count of what
+// was left out.
+//
+// `#[debug(hex)]` prints a byte slice, array or vector as hex. The compact form
+// puts all bytes on one line; the alternate form prints 16 bytes per line.
+// Both can be combined.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+
+#[derive(CustomDebug)]
+pub struct Packet<'a> {
+    #[debug(truncate = 3)]
+    ids: Vec<u32>,
+    #[debug(truncate = 5)]
+    label: &'a str,
+    #[debug(truncate = 1)]
+    headers: BTreeMap<&'static str, u8>,
+    #[debug(hex)]
+    magic: [u8; 4],
+    #[debug(hex, truncate = 20)]
+    payload: &'a [u8],
+}
+
+fn main() {
+    let payload: Vec<u8> = (0..40).collect();
+    let mut headers = BTreeMap::new();
+    headers.insert("a", 1);
+    headers.insert("b", 2);
+
+    let packet = Packet {
+        ids: vec![1, 2, 3, 4, 5],
+        label: "handshake",
+        headers,
+        magic: [0xca, 0xfe, 0xba, 0xbe],
+        payload: &payload,
+    };
+
+    let debug = format!("{:?}", packet);
+    let expected = concat!(
+        r#"Packet { ids: [1, 2, 3, ... (2 more)], label: "hands"... (4 more), "#,
+        r#"headers: {"a": 1, ... (1 more): ...}, magic: [ca fe ba be], "#,
+        r#"payload: [00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 10 11 12 13, ... (20 more)] }"#,
+    );
+    assert_eq!(debug, expected);
+
+    let pretty = format!("{:#?}", packet);
+    let expected = r#"Packet {
+    ids: [
+        1,
+        2,
+        3,
+        ... (2 more),
+    ],
+    label: "hands"... (4 more),
+    headers: {
+        "a": 1,
+        ... (1 more): ...,
+    },
+    magic: [
+        ca fe ba be,
+    ],
+    payload: [
+        00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f,
+        10 11 12 13,
+        ... (20 more),
+    ],
+}"#;
+    assert_eq!(pretty, expected);
+
+    let short = Packet {
+        ids: vec![],
+        label: "",
+        headers: BTreeMap::new(),
+        magic: [0; 4],
+        payload: &[],
+    };
+    assert_eq!(
+        format!("{:?}", short),
+        r#"Packet { ids: [], label: "", headers: {}, magic: [00 00 00 00], payload: [] }"#,
+    );
+}
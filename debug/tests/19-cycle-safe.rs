@@ -0,0 +1,74 @@
+// Printing a graph built from `Rc<RefCell<...>>` with a derived Debug impl
+// recurses forever as soon as the graph has a cycle. With
+// `#[debug(cycle_safe)]` the generated impl remembers which values are being
+// printed on the current thread and prints `<cycle>` when it reaches one of
+// them again.
+//
+// `#[debug(depth = N)]` limits how many of these values are printed inside one
+// another, printing `..` in place of the deeper ones. This is useful for deep
+// but acyclic structures such as long linked lists.
+
+use derive_debug::CustomDebug;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(CustomDebug)]
+#[debug(cycle_safe)]
+pub struct Node {
+    name: &'static str,
+    edges: Vec<Rc<RefCell<Node>>>,
+}
+
+#[derive(CustomDebug)]
+#[debug(depth = 2)]
+pub struct List {
+    value: u8,
+    next: Option<Box<List>>,
+}
+
+fn main() {
+    let a = Rc::new(RefCell::new(Node {
+        name: "a",
+        edges: vec![],
+    }));
+    let b = Rc::new(RefCell::new(Node {
+        name: "b",
+        edges: vec![a.clone()],
+    }));
+    a.borrow_mut().edges.push(b.clone());
+
+    let debug = format!("{:?}", a.borrow());
+    let expected = r#"Node { name: "a", edges: [RefCell { value: Node { name: "b", edges: [RefCell { value: <cycle> }] } }] }"#;
+    assert_eq!(debug, expected);
+
+    // The same node may be printed more than once as long as it is not
+    // nested in itself.
+    let c = Rc::new(RefCell::new(Node {
+        name: "c",
+        edges: vec![],
+    }));
+    let d = Node {
+        name: "d",
+        edges: vec![c.clone(), c],
+    };
+    let debug = format!("{:?}", d);
+    let expected = r#"Node { name: "d", edges: [RefCell { value: Node { name: "c", edges: [] } }, RefCell { value: Node { name: "c", edges: [] } }] }"#;
+    assert_eq!(debug, expected);
+
+    let list = List {
+        value: 0,
+        next: Some(Box::new(List {
+            value: 1,
+            next: Some(Box::new(List {
+                value: 2,
+                next: None,
+            })),
+        })),
+    };
+    let debug = format!("{:?}", list);
+    let expected = "List { value: 0, next: Some(List { value: 1, next: Some(..) }) }";
+    assert_eq!(debug, expected);
+
+    // Break the cycle so the nodes are freed.
+    a.borrow_mut().edges.clear();
+}
@@ -0,0 +1,83 @@
+// Does the generated code still work if prelude items, or the names the
+// expansion might be tempted to use unqualified like `fmt` and `Debug`, mean
+// something different in the caller's code?
+//
+// Like in the builder's test of the same name, everything in the expansion has
+// to be referred to through an absolute path such as ::core::fmt::Debug.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+
+#[allow(dead_code)]
+mod shadowed {
+    pub type Option = ();
+    pub type Some = ();
+    pub type None = ();
+    pub type Result = ();
+    pub type Ok = ();
+    pub type Err = ();
+    pub type Box = ();
+    pub type Debug = ();
+    pub type Display = ();
+    pub type Formatter = ();
+    pub type Hash = ();
+    pub mod fmt {}
+    pub mod core {}
+}
+
+#[allow(unused_imports)]
+use shadowed::*;
+
+#[allow(unused_macros)]
+macro_rules! write {
+    ($($tt:tt)*) => {
+        compile_error!("the local `write!` macro was used")
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! format_args {
+    ($($tt:tt)*) => {
+        compile_error!("the local `format_args!` macro was used")
+    };
+}
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{name}")]
+pub struct Field<'a, T> {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    #[debug(redact = "hash")]
+    secret: T,
+    #[debug(redact = "len")]
+    password: &'a str,
+    #[debug(truncate = 2)]
+    values: [u8; 4],
+    #[debug(hex)]
+    bytes: &'a [u8],
+}
+
+#[derive(CustomDebug)]
+#[debug(cycle_safe, depth = 4)]
+pub enum Tree {
+    Leaf,
+    Node(std::vec::Vec<Tree>),
+}
+
+fn main() {
+    let field = Field {
+        name: "F",
+        bitmask: 0b0011_1100,
+        secret: 7u32,
+        password: "hunter2",
+        values: [1, 2, 3, 4],
+        bytes: &[0xab, 0xcd],
+    };
+    let debug = std::format!("{:?}", field);
+    assert!(debug.starts_with(r#"Field { name: "F", bitmask: 0b00111100, secret: [REDACTED hash="#));
+    assert!(debug.ends_with(r#"password: [REDACTED len=7], values: [1, 2, ... (2 more)], bytes: [ab cd] }"#));
+    assert_eq!(std::format!("{}", field), "F");
+
+    let tree = Tree::Node(std::vec![Tree::Leaf]);
+    assert_eq!(std::format!("{:?}", tree), "Node([Leaf])");
+}
@@ -0,0 +1,56 @@
+// The generated impls must also compile in `#![no_std]` crates. These have
+// neither the std prelude nor `std` in scope, so any reference to std in the
+// expansion fails to resolve.
+//
+// The crate still links std, under a different name, so that the test can be
+// run as a regular binary.
+//
+// The `tag` field's bound is overridden, since `Tag<T>` is Display for any `T`.
+
+#![no_std]
+
+extern crate std as runtime;
+
+use core::fmt;
+use core::marker::PhantomData;
+use derive_debug::{CustomDebug, CustomDisplay};
+
+pub struct NotDisplay;
+
+pub struct Tag<T>(PhantomData<T>);
+
+impl<T> fmt::Display for Tag<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("!")
+    }
+}
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{count} x {name}{tag}")]
+pub struct Item<T> {
+    name: &'static str,
+    #[debug = "{:#x}"]
+    count: u32,
+    #[debug(skip)]
+    #[display(bound = "")]
+    tag: Tag<T>,
+}
+
+#[derive(CustomDebug)]
+pub enum State {
+    Empty,
+    Full(#[debug(truncate = 1)] &'static [u8]),
+}
+
+fn main() {
+    let item = Item {
+        name: "apple",
+        count: 12,
+        tag: Tag::<NotDisplay>(PhantomData),
+    };
+    assert_eq!(runtime::format!("{:?}", item), r#"Item { name: "apple", count: 0xc, .. }"#);
+    assert_eq!(runtime::format!("{}", item), "12 x apple!");
+
+    assert_eq!(runtime::format!("{:?}", State::Empty), "Empty");
+    assert_eq!(runtime::format!("{:?}", State::Full(&[1, 2])), "Full([1, ... (1 more)])");
+}
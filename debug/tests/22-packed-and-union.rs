@@ -0,0 +1,61 @@
+// Taking a reference to a field of a `#[repr(packed)]` struct is an error,
+// because the field may not be aligned. The generated impl for a packed
+// struct copies each printed field to a local before formatting it, which
+// requires the fields to be Copy.
+//
+// Unions can be derived too. Since the impl cannot know which field is
+// initialized, it either calls the function given by
+// `#[debug(union_fmt = "...")]` or prints only the size of the union.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+#[derive(CustomDebug)]
+#[repr(C, packed)]
+pub struct Header<T> {
+    tag: u8,
+    #[debug = "{:#06x}"]
+    len: u32,
+    value: T,
+    #[debug(skip)]
+    _name: String,
+}
+
+#[derive(CustomDebug)]
+#[repr(packed(2))]
+#[debug(transparent)]
+pub struct Wrapper(u64);
+
+#[derive(CustomDebug)]
+#[debug(union_fmt = "fmt_number")]
+pub union Number {
+    int: u32,
+    float: f32,
+}
+
+fn fmt_number(number: &Number, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Number({:#x})", unsafe { number.int })
+}
+
+#[derive(CustomDebug)]
+pub union Opaque {
+    _bytes: [u8; 8],
+    _word: u64,
+}
+
+fn main() {
+    let header = Header {
+        tag: 1,
+        len: 0x20,
+        value: 'x',
+        _name: String::new(),
+    };
+    assert_eq!(
+        format!("{:?}", header),
+        "Header { tag: 1, len: 0x0020, value: 'x', .. }",
+    );
+    assert_eq!(format!("{:?}", Wrapper(7)), "7");
+
+    assert_eq!(format!("{:?}", Number { float: 1.0 }), "Number(0x3f800000)");
+    assert_eq!(format!("{:?}", Opaque { _word: 0 }), "Opaque(<8 bytes>)");
+}
@@ -0,0 +1,13 @@
+// Fields of a packed struct that are printed need to be Copy. The error should
+// point at the offending field rather than at some part of the generated code.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[repr(packed)]
+pub struct Packet {
+    id: u16,
+    payload: Vec<u8>,
+}
+
+fn main() {}
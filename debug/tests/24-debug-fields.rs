@@ -0,0 +1,100 @@
+// Structured loggers want the fields of a value as key/value pairs rather than
+// one formatted string. Alongside the Debug impl, the derive implements the
+// `DebugFields` trait, whose `debug_fields` method reports each field to a
+// `FieldVisitor` with the name and formatting it would have in the Debug
+// output. Being a trait method, it does not clash with an inherent method of
+// the same name on the type.
+//
+// Closures taking a name and a `&dyn Debug` implement FieldVisitor.
+
+use derive_debug::{CustomDebug, DebugFields, FieldVisitor};
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "camelCase")]
+pub struct Request<T> {
+    request_id: u64,
+    #[debug = "{}ms"]
+    elapsed_ms: u32,
+    #[debug(rename = "user")]
+    user_name: T,
+    #[debug(redact)]
+    token: String,
+    #[debug(skip)]
+    _internal: (),
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Started,
+    Progress(u8),
+    Finished { ok: bool },
+}
+
+#[derive(CustomDebug)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    pub fn debug_fields(&self) -> usize {
+        2
+    }
+}
+
+fn log_fields<T: DebugFields>(value: &T) -> Vec<String> {
+    let mut names = vec![];
+    value.debug_fields(&mut |name: &str, _: &dyn Debug| names.push(name.to_owned()));
+    names
+}
+
+#[derive(Default)]
+struct Collect(Vec<(String, String)>);
+
+impl FieldVisitor for Collect {
+    fn visit(&mut self, name: &str, value: &dyn Debug) {
+        self.0.push((name.to_owned(), format!("{:?}", value)));
+    }
+}
+
+fn main() {
+    let request = Request {
+        request_id: 7,
+        elapsed_ms: 42,
+        user_name: "ferris",
+        token: "secret".to_owned(),
+        _internal: (),
+    };
+    let mut collect = Collect::default();
+    request.debug_fields(&mut collect);
+    let expected = [
+        ("requestId", "7"),
+        ("elapsedMs", "42ms"),
+        ("user", "\"ferris\""),
+        ("token", "[REDACTED]"),
+    ];
+    let expected: Vec<(String, String)> = expected
+        .iter()
+        .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+        .collect();
+    assert_eq!(collect.0, expected);
+
+    let mut names = vec![];
+    Event::Finished { ok: true }.debug_fields(&mut |name: &str, _: &dyn Debug| {
+        names.push(name.to_owned());
+    });
+    assert_eq!(names, ["ok"]);
+
+    let mut values = vec![];
+    Event::Progress(50).debug_fields(&mut |_: &str, value: &dyn Debug| {
+        values.push(format!("{:?}", value));
+    });
+    assert_eq!(values, ["50"]);
+
+    Event::Started.debug_fields(&mut |_: &str, _: &dyn Debug| unreachable!());
+
+    let point = Point { x: 1, y: 2 };
+    assert_eq!(point.debug_fields(), 2);
+    assert_eq!(log_fields(&point), ["x", "y"]);
+}
@@ -0,0 +1,54 @@
+// The iteration order of a HashMap or HashSet changes from one run to the
+// next, which makes Debug output of types containing them useless for snapshot
+// tests. `#[debug(sorted)]` prints a map or set sorted by the Debug output of
+// its keys. `#[debug(deterministic)]` on the type does the same for every
+// HashMap and HashSet field.
+//
+// Sorting happens before truncation, so the same elements are left out on
+// every run.
+
+use derive_debug::CustomDebug;
+use std::collections::{HashMap, HashSet};
+
+#[derive(CustomDebug)]
+pub struct Config<'a> {
+    #[debug(sorted)]
+    env: HashMap<&'static str, &'static str>,
+    #[debug(sorted, truncate = 2)]
+    features: &'a HashSet<u32>,
+}
+
+#[derive(CustomDebug)]
+#[debug(deterministic)]
+pub enum Index {
+    Words(HashSet<String>),
+    Counts { counts: HashMap<char, usize>, total: usize },
+}
+
+fn main() {
+    let mut env = HashMap::new();
+    for (key, value) in [("PATH", "/bin"), ("HOME", "/root"), ("LANG", "C"), ("TERM", "xterm")] {
+        env.insert(key, value);
+    }
+    let features: HashSet<u32> = (1..=5).collect();
+    let config = Config {
+        env,
+        features: &features,
+    };
+    assert_eq!(
+        format!("{:?}", config),
+        r#"Config { env: {"HOME": "/root", "LANG": "C", "PATH": "/bin", "TERM": "xterm"}, features: {1, 2, ... (3 more)} }"#,
+    );
+
+    let words = ["pear", "apple", "fig"].iter().map(|word| word.to_string()).collect();
+    assert_eq!(
+        format!("{:?}", Index::Words(words)),
+        r#"Words({"apple", "fig", "pear"})"#,
+    );
+
+    let counts: HashMap<char, usize> = "hello".chars().map(|c| (c, "hello".matches(c).count())).collect();
+    assert_eq!(
+        format!("{:?}", Index::Counts { counts, total: 5 }),
+        "Counts { counts: {'e': 1, 'h': 1, 'l': 2, 'o': 1}, total: 5 }",
+    );
+}
@@ -0,0 +1,12 @@
+// Only maps and sets can be sorted. A sorted Vec would hide the order of its
+// elements, which is part of the value.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Queue {
+    #[debug(sorted)]
+    jobs: Vec<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,78 @@
+// The format string of `#[debug = "..."]` may refer to the other fields of the
+// same struct or variant by name, or as `{self.1}` by index in a tuple struct.
+// A field named by a raw identifier is referred to as `{r#type}`.
+// Placeholders without a name, and `{0}`, format the field the attribute is
+// on, which is the only positional argument.
+//
+// A field referred to this way is formatted through the trait its format spec
+// asks for, and is available even if it is itself skipped.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Measurement<U> {
+    #[debug = "{:.2}{unit}"]
+    value: f64,
+    #[debug(skip)]
+    unit: U,
+    #[debug = "{}/{max}"]
+    retries: u8,
+    max: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Range(#[debug = "{}..{self.1}"] u32, #[debug(skip)] u32);
+
+#[derive(CustomDebug)]
+pub struct Pair(&'static str, #[debug = "{0:#x}"] u8);
+
+#[derive(CustomDebug)]
+pub struct Named {
+    #[debug = "{0:#x}/{:o}"]
+    a: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Token {
+    #[debug = "{} {r#type}"]
+    text: &'static str,
+    r#type: u8,
+}
+
+#[derive(CustomDebug)]
+pub enum Reading {
+    Temperature {
+        #[debug = "{:.1}°{scale}"]
+        degrees: f32,
+        #[debug(skip)]
+        scale: char,
+    },
+    Pressure(#[debug = "{} {self.1}"] u32, #[debug(skip)] &'static str),
+}
+
+fn main() {
+    let measurement = Measurement {
+        value: 3.14159,
+        unit: "kg",
+        retries: 2,
+        max: 5,
+    };
+    assert_eq!(
+        format!("{:?}", measurement),
+        "Measurement { value: 3.14kg, retries: 2/5, max: 5, .. }",
+    );
+
+    assert_eq!(format!("{:?}", Range(1, 4)), "Range(1..4, ..)");
+    assert_eq!(format!("{:?}", Pair("p", 255)), r#"Pair("p", 0xff)"#);
+    assert_eq!(format!("{:?}", Named { a: 8 }), "Named { a: 0x8/10 }");
+
+    let token = Token { text: "fn", r#type: 2 };
+    assert_eq!(format!("{:?}", token), "Token { text: fn 2, type: 2 }");
+
+    let reading = Reading::Temperature {
+        degrees: 21.56,
+        scale: 'C',
+    };
+    assert_eq!(format!("{:?}", reading), "Temperature { degrees: 21.6°C, .. }");
+    assert_eq!(format!("{:?}", Reading::Pressure(1013, "hPa")), "Pressure(1013 hPa, ..)");
+}
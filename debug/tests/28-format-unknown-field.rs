@@ -0,0 +1,13 @@
+// Placeholders naming a field that does not exist are reported against the
+// attribute while expanding the derive.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Measurement {
+    #[debug = "{:.2}{units}"]
+    value: f64,
+    unit: &'static str,
+}
+
+fn main() {}
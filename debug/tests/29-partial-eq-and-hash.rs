@@ -0,0 +1,100 @@
+// Fields left out of the Debug output, like caches, usually have to be left
+// out of equality and hashing as well. CustomPartialEq and CustomHash compare
+// and hash every field except those with `#[debug(skip)]`, or with
+// `#[cmp(skip)]` for fields that should still be printed.
+//
+// Their bounds are inferred the same way as for CustomDebug: type parameters
+// only used in PhantomData are not bounded, and those used through an
+// associated type bound the associated type.
+
+use derive_debug::{CustomDebug, CustomHash, CustomPartialEq};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+pub trait Store {
+    type Key;
+}
+
+pub struct Memory;
+
+impl Store for Memory {
+    type Key = u32;
+}
+
+#[derive(CustomDebug, CustomPartialEq, CustomHash)]
+pub struct Entry<S: Store, M> {
+    key: S::Key,
+    name: String,
+    #[cmp(skip)]
+    updated_at: u64,
+    #[debug(skip)]
+    cache: Option<Vec<u8>>,
+    marker: PhantomData<M>,
+}
+
+#[derive(CustomPartialEq, CustomHash)]
+pub enum Shape {
+    Empty,
+    Circle(u32),
+    Rect {
+        width: u32,
+        height: u32,
+        #[cmp(skip)]
+        _label: &'static str,
+    },
+}
+
+#[derive(CustomPartialEq, CustomHash)]
+#[repr(packed)]
+pub struct Packed {
+    a: u8,
+    b: u32,
+    #[cmp(skip)]
+    _c: u16,
+}
+
+struct NotComparable;
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn entry(name: &str, updated_at: u64, cache: Option<Vec<u8>>) -> Entry<Memory, NotComparable> {
+    Entry {
+        key: 1,
+        name: name.to_owned(),
+        updated_at,
+        cache,
+        marker: PhantomData,
+    }
+}
+
+fn main() {
+    let a = entry("a", 10, None);
+    let b = entry("a", 20, Some(vec![1, 2, 3]));
+    let c = entry("c", 10, None);
+    assert!(a == b);
+    assert!(a != c);
+    assert_eq!(hash(&a), hash(&b));
+    assert_ne!(hash(&a), hash(&c));
+
+    let rect = |_label| Shape::Rect {
+        width: 2,
+        height: 3,
+        _label,
+    };
+    assert!(rect("x") == rect("y"));
+    assert_eq!(hash(&rect("x")), hash(&rect("y")));
+    assert!(Shape::Empty == Shape::Empty);
+    assert!(Shape::Circle(1) != Shape::Circle(2));
+    assert!(Shape::Circle(1) != Shape::Empty);
+    assert_ne!(hash(&Shape::Empty), hash(&Shape::Circle(0)));
+
+    let p = Packed { a: 1, b: 2, _c: 3 };
+    let q = Packed { a: 1, b: 2, _c: 4 };
+    assert!(p == q);
+    assert_eq!(hash(&p), hash(&q));
+}
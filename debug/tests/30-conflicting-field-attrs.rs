@@ -0,0 +1,33 @@
+// A field can be printed in only one way. Combining options that would
+// override one another, such as a format string and a formatting function, is
+// an error pointing at the option that conflicts, rather than one of them
+// silently winning. Truncation combines with `hex` and `sorted`, which print
+// collections.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+fn fmt_code(code: &u8, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "#{}", code)
+}
+
+#[derive(CustomDebug)]
+pub struct Formatted {
+    #[debug = "{:x}"]
+    #[debug(with = "fmt_code")]
+    code: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Redacted {
+    #[debug(redact, hex)]
+    key: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+pub struct Skipped {
+    #[debug(skip, truncate = 2)]
+    items: Vec<u8>,
+}
+
+fn main() {}
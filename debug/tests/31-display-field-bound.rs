@@ -0,0 +1,34 @@
+// As with `#[debug(bound = "...")]`, `#[display(bound = "...")]` on a field
+// replaces the bounds CustomDisplay infers from that field only, here because
+// `Count<T>` is Display whatever `T` is.
+
+use derive_debug::CustomDisplay;
+use std::fmt::{self, Display};
+
+pub struct NotDisplay;
+
+pub struct Count<T>(Vec<T>);
+
+impl<T> Display for Count<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} items", self.0.len())
+    }
+}
+
+#[derive(CustomDisplay)]
+#[display("{name}: {items} of {label}")]
+pub struct Bag<T, L> {
+    name: &'static str,
+    #[display(bound = "")]
+    items: Count<T>,
+    label: L,
+}
+
+fn main() {
+    let bag = Bag {
+        name: "bag",
+        items: Count(vec![NotDisplay, NotDisplay]),
+        label: "things",
+    };
+    assert_eq!(bag.to_string(), "bag: 2 items of things");
+}
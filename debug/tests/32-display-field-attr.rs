@@ -0,0 +1,21 @@
+// The only attribute CustomDisplay accepts on a field is `bound`; anything
+// else is an error rather than being ignored.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{a}")]
+pub struct Field {
+    a: u8,
+    #[display(totally_bogus)]
+    b: u8,
+}
+
+#[derive(CustomDisplay)]
+#[display("{a}")]
+pub struct Template {
+    #[display("{}")]
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+// Format specs may take their width or precision from a field, as in
+// `{:>width$}` or `{:.1$}`. These refer to fields the same way as the
+// placeholders themselves, and the field is passed by value as the `usize`
+// that `format_args!` expects.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+
+#[derive(CustomDebug)]
+pub struct Cell {
+    #[debug = "[{:>width$}]"]
+    text: &'static str,
+    #[debug(skip)]
+    width: usize,
+    #[debug = "{:.prec$}"]
+    value: f64,
+    prec: usize,
+}
+
+#[derive(CustomDebug)]
+pub struct Padded(#[debug = "{:0self.1$}"] u32, #[debug(skip)] usize);
+
+#[derive(CustomDisplay)]
+#[display("{name:^width$}|{ratio:.digits$}")]
+pub struct Column {
+    name: &'static str,
+    width: usize,
+    ratio: f32,
+    digits: usize,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0:.1$}")]
+pub struct Rounded(f64, usize);
+
+fn main() {
+    let cell = Cell {
+        text: "ab",
+        width: 4,
+        value: 1.23456,
+        prec: 2,
+    };
+    assert_eq!(
+        format!("{:?}", cell),
+        "Cell { text: [  ab], value: 1.23, prec: 2, .. }",
+    );
+    assert_eq!(format!("{:?}", Padded(7, 3)), "Padded(007, ..)");
+
+    let column = Column {
+        name: "id",
+        width: 6,
+        ratio: 0.5,
+        digits: 2,
+    };
+    assert_eq!(column.to_string(), "  id  |0.50");
+    assert_eq!(Rounded(3.14159, 3).to_string(), "3.142");
+}
@@ -0,0 +1,26 @@
+// In the format string of `#[debug = "..."]` the field itself is the only
+// positional argument. Other positions, including those giving a width, and
+// `.*`, which takes the precision from the next positional argument, are
+// reported against the attribute.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Range(#[debug = "{}..{1}"] u32, u32);
+
+#[derive(CustomDebug)]
+pub struct Width(#[debug = "{:1$}"] u32, usize);
+
+#[derive(CustomDebug)]
+pub struct Star {
+    #[debug = "{:.*}"]
+    value: f64,
+}
+
+#[derive(CustomDebug)]
+pub struct Unknown {
+    #[debug = "{:width$}"]
+    value: f64,
+}
+
+fn main() {}
@@ -1,12 +1,38 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enums-and-tuple-structs.rs");
+    t.pass("tests/10-skip-and-redact.rs");
+    t.pass("tests/11-with-function.rs");
+    t.pass("tests/12-display.rs");
+    t.compile_fail("tests/13-display-unknown-field.rs");
+    t.pass("tests/14-rename.rs");
+    t.pass("tests/15-transparent.rs");
+    t.compile_fail("tests/16-transparent-many-fields.rs");
+    t.pass("tests/17-field-bound.rs");
+    t.pass("tests/18-truncate-and-hex.rs");
+    t.pass("tests/19-cycle-safe.rs");
+    t.pass("tests/20-redefined-prelude-types.rs");
+    t.pass("tests/21-no-std.rs");
+    t.pass("tests/22-packed-and-union.rs");
+    t.compile_fail("tests/23-packed-not-copy.rs");
+    t.pass("tests/24-debug-fields.rs");
+    t.pass("tests/25-sorted.rs");
+    t.compile_fail("tests/26-sorted-not-collection.rs");
+    t.pass("tests/27-format-sibling-fields.rs");
+    t.compile_fail("tests/28-format-unknown-field.rs");
+    t.pass("tests/29-partial-eq-and-hash.rs");
+    t.compile_fail("tests/30-conflicting-field-attrs.rs");
+    t.pass("tests/31-display-field-bound.rs");
+    t.compile_fail("tests/32-display-field-attr.rs");
+    t.pass("tests/33-format-width-from-field.rs");
+    t.compile_fail("tests/34-format-positional-errors.rs");
 }
@@ -0,0 +1,180 @@
+//! Snapshot testing of macro expansions without invoking the compiler.
+//!
+//! Every `*.rs` file in a fixtures directory holds the input item of a macro.
+//! The item is handed to an expansion function operating on
+//! `proc_macro2::TokenStream`, the output is pretty-printed and compared
+//! against the `*.expanded.rs` file next to it.
+//!
+//! A missing snapshot is written but still fails the test, so that a deleted
+//! or forgotten `*.expanded.rs` file is caught; review and commit the new
+//! file, then rerun. Run the tests with `EXPANDTEST=overwrite` to write
+//! missing snapshots and accept changed expansions without failing.
+
+use proc_macro2::TokenStream;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EXPANDED_SUFFIX: &str = ".expanded.rs";
+
+/// Expands every fixture in `dir` with `expand` and compares the result
+/// against its checked-in snapshot, panicking with a line diff of every
+/// expansion that does not match and the paths of snapshots that had to be
+/// written.
+pub fn run<P, F>(dir: P, expand: F)
+where
+    P: AsRef<Path>,
+    F: Fn(TokenStream) -> TokenStream,
+{
+    let overwrite = std::env::var("EXPANDTEST").as_deref() == Ok("overwrite");
+    let mut failures = vec![];
+
+    for fixture in fixtures(dir.as_ref()) {
+        let source = read(&fixture);
+        let input: TokenStream = source
+            .parse()
+            .unwrap_or_else(|err| panic!("failed to tokenize {}: {}", fixture.display(), err));
+        let actual = pretty_print(expand(input), &fixture);
+
+        let snapshot = snapshot_path(&fixture);
+        if overwrite {
+            write(&snapshot, &actual);
+            eprintln!("wrote {}", snapshot.display());
+            continue;
+        }
+        if !snapshot.exists() {
+            write(&snapshot, &actual);
+            eprintln!("expand {} ... wip", fixture.display());
+            failures.push(format!(
+                "{}\n\nNo snapshot existed, wrote {}; review it and commit it.",
+                fixture.display(),
+                snapshot.display(),
+            ));
+            continue;
+        }
+
+        let expected = read(&snapshot);
+        if normalize(&expected) == normalize(&actual) {
+            eprintln!("expand {} ... ok", fixture.display());
+        } else {
+            eprintln!("expand {} ... mismatch", fixture.display());
+            failures.push(format!(
+                "{}\n\n--- {}\n+++ expansion\n{}",
+                fixture.display(),
+                snapshot.display(),
+                diff(&normalize(&expected), &normalize(&actual)),
+            ));
+        }
+    }
+
+    if !failures.is_empty() {
+        panic!(
+            "{} expansion(s) did not match a checked-in snapshot; rerun with \
+             EXPANDTEST=overwrite to accept them\n\n{}",
+            failures.len(),
+            failures.join("\n\n"),
+        );
+    }
+}
+
+fn fixtures(dir: &Path) -> Vec<PathBuf> {
+    let entries = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", dir.display(), err));
+
+    let mut fixtures: Vec<PathBuf> = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some(OsStr::new("rs")))
+        .filter(|path| !path.to_string_lossy().ends_with(EXPANDED_SUFFIX))
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+fn snapshot_path(fixture: &Path) -> PathBuf {
+    let stem = fixture.file_stem().unwrap().to_string_lossy();
+    fixture.with_file_name(format!("{}{}", stem, EXPANDED_SUFFIX))
+}
+
+fn pretty_print(tokens: TokenStream, fixture: &Path) -> String {
+    match syn::parse2::<syn::File>(tokens.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(err) => panic!(
+            "expansion of {} is not a valid file: {}\n{}",
+            fixture.display(),
+            err,
+            tokens,
+        ),
+    }
+}
+
+/// Number of unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// Line diff of `expected` against `actual`, showing removed lines with `-`
+/// and added lines with `+` around `CONTEXT` unchanged lines.
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..]
+    // and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = (0..lines.len()).filter(|&k| lines[k].0 != ' ').collect();
+    let near_change = |k: usize| changed.iter().any(|&c| k + CONTEXT >= c && k <= c + CONTEXT);
+    let mut out = String::new();
+    let mut skipped = false;
+    for (k, (tag, line)) in lines.iter().enumerate() {
+        if near_change(k) {
+            if skipped {
+                out.push_str("...\n");
+                skipped = false;
+            }
+            out.push(*tag);
+            out.push_str(line);
+            out.push('\n');
+        } else {
+            skipped = true;
+        }
+    }
+    out
+}
+
+fn normalize(s: &str) -> String {
+    s.replace("\r\n", "\n").trim_end().to_owned()
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", path.display(), err))
+}
+
+fn write(path: &Path, contents: &str) {
+    fs::write(path, contents)
+        .unwrap_or_else(|err| panic!("failed to write {}: {}", path.display(), err))
+}
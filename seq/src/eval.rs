@@ -0,0 +1,140 @@
+//! Constant integer expressions, as accepted in the bounds of a range and in
+//! `#{...}` within the body, where the loop variables have been replaced by
+//! their values beforehand.
+//!
+//! Supports integer and char literals, `+ - * / %`, unary minus and
+//! parentheses. Arithmetic is checked, so that overflow, including overflow
+//! of the type named by a literal's suffix, or division by zero are reported
+//! as errors at the offending operator.
+
+use crate::value::{IntFormat, Value};
+use proc_macro2::Span;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Error, Ident, LitChar, LitInt, Result, Token};
+
+pub enum Expr {
+    Int(LitInt),
+    Char(LitChar),
+    Neg(Span, Box<Expr>),
+    Binary(Box<Expr>, BinOp, Span, Box<Expr>),
+}
+
+#[derive(Copy, Clone)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Parse for Expr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut expr = parse_term(input)?;
+        loop {
+            let op = if input.peek(Token![+]) {
+                BinOp::Add
+            } else if input.peek(Token![-]) {
+                BinOp::Sub
+            } else {
+                return Ok(expr);
+            };
+            let span = input.parse::<proc_macro2::Punct>()?.span();
+            let rhs = parse_term(input)?;
+            expr = Expr::Binary(Box::new(expr), op, span, Box::new(rhs));
+        }
+    }
+}
+
+fn parse_term(input: ParseStream) -> Result<Expr> {
+    let mut expr = parse_unary(input)?;
+    loop {
+        let op = if input.peek(Token![*]) {
+            BinOp::Mul
+        } else if input.peek(Token![/]) {
+            BinOp::Div
+        } else if input.peek(Token![%]) {
+            BinOp::Rem
+        } else {
+            return Ok(expr);
+        };
+        let span = input.parse::<proc_macro2::Punct>()?.span();
+        let rhs = parse_unary(input)?;
+        expr = Expr::Binary(Box::new(expr), op, span, Box::new(rhs));
+    }
+}
+
+fn parse_unary(input: ParseStream) -> Result<Expr> {
+    if input.peek(Token![-]) {
+        let minus: Token![-] = input.parse()?;
+        let operand = parse_unary(input)?;
+        return Ok(Expr::Neg(minus.span, Box::new(operand)));
+    }
+
+    let lookahead = input.lookahead1();
+    if lookahead.peek(LitInt) {
+        input.parse().map(Expr::Int)
+    } else if lookahead.peek(LitChar) {
+        input.parse().map(Expr::Char)
+    } else if lookahead.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        content.parse()
+    } else if input.peek(Ident) {
+        let ident: Ident = input.parse()?;
+        let msg = format!("expected an integer, a char or a loop variable, found `{}`", ident);
+        Err(Error::new(ident.span(), msg))
+    } else {
+        Err(lookahead.error())
+    }
+}
+
+impl Expr {
+    pub fn eval(&self) -> Result<Value> {
+        match self {
+            Expr::Int(lit) => {
+                let format = IntFormat::of(&lit.to_string(), lit.suffix());
+                Ok(Value::Int(lit.base10_parse()?, format))
+            }
+            Expr::Char(lit) => Ok(Value::Char(lit.value())),
+            Expr::Neg(span, operand) => {
+                let (n, format) = int(operand.eval()?, *span)?;
+                let n = n.checked_neg();
+                Ok(Value::Int(in_limits(n, &format, *span)?, format))
+            }
+            Expr::Binary(lhs, op, span, rhs) => {
+                let (a, lhs_format) = int(lhs.eval()?, *span)?;
+                let (b, rhs_format) = int(rhs.eval()?, *span)?;
+                let format = lhs_format.merge(&rhs_format, *span)?;
+                let n = match op {
+                    BinOp::Add => a.checked_add(b),
+                    BinOp::Sub => a.checked_sub(b),
+                    BinOp::Mul => a.checked_mul(b),
+                    BinOp::Div | BinOp::Rem if b == 0 => {
+                        return Err(Error::new(*span, "attempt to divide by zero"));
+                    }
+                    BinOp::Div => a.checked_div(b),
+                    BinOp::Rem => a.checked_rem(b),
+                };
+                Ok(Value::Int(in_limits(n, &format, *span)?, format))
+            }
+        }
+    }
+}
+
+fn int(value: Value, span: Span) -> Result<(i128, IntFormat)> {
+    match value {
+        Value::Int(n, format) => Ok((n, format)),
+        Value::Char(_) => Err(Error::new(span, "arithmetic is only supported on integers")),
+    }
+}
+
+/// The result of checked arithmetic, which must also fit in the type named by
+/// the suffix of the operands.
+fn in_limits(n: Option<i128>, format: &IntFormat, span: Span) -> Result<i128> {
+    let fits = |n: &i128| match format.limits() {
+        Some((min, max)) => min <= *n && *n <= max,
+        None => true,
+    };
+    n.filter(fits).ok_or_else(|| Error::new(span, "arithmetic overflow"))
+}
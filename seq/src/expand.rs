@@ -0,0 +1,333 @@
+//! Parsing of a `seq!` invocation and expansion of its body.
+
+use crate::eval::Expr;
+use crate::range::{Range, MAX_VALUES};
+use crate::value::{PasteFormat, Value};
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, parenthesized, token, Error, Result, Token};
+
+/// `N in 0..8 { ... }`, or `(I, J) in 0..4, 0..8 { ... }` to iterate over
+/// every combination of values of several variables.
+pub struct Seq {
+    vars: Vec<Ident>,
+    in_token: Token![in],
+    ranges: Vec<Range>,
+    body: TokenStream,
+}
+
+/// The value of each loop variable in one repetition of the body.
+type Bindings = Vec<(Ident, Value)>;
+
+impl Parse for Seq {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let vars: Vec<Ident> = if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let vars = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+            vars.into_iter().collect()
+        } else {
+            vec![input.parse()?]
+        };
+        let in_token: Token![in] = input.parse()?;
+
+        let mut ranges = vec![input.parse()?];
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            ranges.push(input.parse()?);
+        }
+        if ranges.len() != vars.len() {
+            return Err(Error::new(
+                in_token.span,
+                format!("expected {} ranges, one for each variable, found {}", vars.len(), ranges.len()),
+            ));
+        }
+
+        let content;
+        braced!(content in input);
+        let body = content.parse()?;
+        Ok(Seq {
+            vars,
+            in_token,
+            ranges,
+            body,
+        })
+    }
+}
+
+impl Seq {
+    /// The body repeated for every value, or the body with each of its
+    /// `#(...)*` sections repeated if it has any.
+    pub fn expand(&self) -> Result<TokenStream> {
+        let iterations = self.iterations()?;
+        let (expanded, has_sections) = expand_sections(self.body.clone(), &iterations)?;
+        if has_sections {
+            return Ok(expanded);
+        }
+
+        let mut output = TokenStream::new();
+        for bindings in &iterations {
+            output.extend(substitute(self.body.clone(), bindings, &[])?);
+        }
+        Ok(output)
+    }
+
+    /// Every combination of values of the variables, varying the last
+    /// variable fastest.
+    fn iterations(&self) -> Result<Vec<Bindings>> {
+        let values = self.ranges.iter().map(Range::values).collect::<Result<Vec<_>>>()?;
+        let combinations = values
+            .iter()
+            .try_fold(1u128, |product, values| product.checked_mul(values.len() as u128));
+        if combinations.is_none_or(|combinations| combinations > MAX_VALUES) {
+            return Err(Error::new(
+                self.in_token.span,
+                format!(
+                    "ranges have more than {} combinations of values, which is the most seq! expands",
+                    MAX_VALUES,
+                ),
+            ));
+        }
+
+        let mut iterations = vec![Bindings::new()];
+        for (var, values) in self.vars.iter().zip(&values) {
+            iterations = iterations
+                .into_iter()
+                .flat_map(|bindings| {
+                    values.iter().map(move |value| {
+                        let mut bindings = bindings.clone();
+                        bindings.push((var.clone(), value.clone()));
+                        bindings
+                    })
+                })
+                .collect();
+        }
+        Ok(iterations)
+    }
+}
+
+/// Repeats every `#(...)*` section in `tokens`, returning whether there was
+/// any. Sections of nested `seq!` invocations are left for those to repeat.
+fn expand_sections(tokens: TokenStream, iterations: &[Bindings]) -> Result<(TokenStream, bool)> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut output = TokenStream::new();
+    let mut found = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some(section) = section(&tokens[i..]) {
+            for bindings in iterations {
+                output.extend(substitute(section.stream(), bindings, &[])?);
+            }
+            found = true;
+            i += 3;
+            continue;
+        }
+        if nested_seq(&tokens[i..]).is_some() {
+            output.extend(tokens[i..i + 3].iter().cloned());
+            i += 3;
+            continue;
+        }
+        match &tokens[i] {
+            TokenTree::Group(group) => {
+                let (stream, nested) = expand_sections(group.stream(), iterations)?;
+                output.extend(Some(TokenTree::Group(with_stream(group, stream))));
+                found |= nested;
+            }
+            token => output.extend(Some(token.clone())),
+        }
+        i += 1;
+    }
+    Ok((output, found))
+}
+
+/// Replaces the loop variables in `tokens` by their values, pasting them into
+/// identifiers written as `prefix#N`. An expression `#{N * 4}` is replaced by
+/// its value, or pasted as in `next#{N + 1}` or, formatted, `reg#{N:02}`.
+///
+/// Within a nested `seq!` invocation the variables it binds itself shadow
+/// those of the outer one, while the others are substituted as usual, which
+/// lets the inner invocation paste `m#I#J` after the outer one made it
+/// `m0#J`. Expressions using shadowed variables are left for the inner
+/// invocation to evaluate.
+fn substitute(tokens: TokenStream, bindings: &Bindings, shadowed: &[Ident]) -> Result<TokenStream> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut output = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some(group) = nested_seq(&tokens[i..]) {
+            let mut shadowed = shadowed.to_vec();
+            shadowed.extend(bound_vars(group));
+            let visible: Bindings = bindings
+                .iter()
+                .filter(|(var, _)| !shadowed.contains(var))
+                .cloned()
+                .collect();
+            let stream = substitute(group.stream(), &visible, &shadowed)?;
+            output.extend(tokens[i..i + 2].iter().cloned());
+            output.push(TokenTree::Group(with_stream(group, stream)));
+            i += 3;
+            continue;
+        }
+
+        match &tokens[i] {
+            TokenTree::Ident(ident) => match lookup(bindings, ident) {
+                Some(value) => output.push(TokenTree::Literal(value.to_literal(ident.span()))),
+                None => output.push(TokenTree::Ident(ident.clone())),
+            },
+            TokenTree::Punct(pound) if pound.as_char() == '#' && section(&tokens[i..]).is_none() => {
+                let pasting = matches!(output.last(), Some(TokenTree::Ident(_)));
+                let value = match tokens.get(i + 1) {
+                    Some(TokenTree::Ident(var)) => lookup(bindings, var)
+                        .map(|value| (var.span(), value.clone(), PasteFormat::default())),
+                    Some(TokenTree::Group(expr)) if expr.delimiter() == Delimiter::Brace => {
+                        evaluate(expr, bindings, shadowed, pasting)?
+                            .map(|(value, format)| (expr.span(), value, format))
+                    }
+                    _ => None,
+                };
+                let is_expr = matches!(tokens.get(i + 1), Some(TokenTree::Group(_)));
+                match (output.last(), value) {
+                    (Some(TokenTree::Ident(prefix)), Some((span, value, format))) => {
+                        let ident = paste(prefix, span, &value, &format)?;
+                        output.pop();
+                        output.push(TokenTree::Ident(ident));
+                        i += 1;
+                    }
+                    (_, Some((span, value, _))) if is_expr => {
+                        output.push(TokenTree::Literal(value.to_literal(span)));
+                        i += 1;
+                    }
+                    _ => output.push(tokens[i].clone()),
+                }
+            }
+            TokenTree::Group(group) => {
+                let stream = substitute(group.stream(), bindings, shadowed)?;
+                output.push(TokenTree::Group(with_stream(group, stream)));
+            }
+            token => output.push(token.clone()),
+        }
+        i += 1;
+    }
+    Ok(output.into_iter().collect())
+}
+
+fn lookup<'a>(bindings: &'a Bindings, ident: &Ident) -> Option<&'a Value> {
+    bindings
+        .iter()
+        .find(|(var, _)| var == ident)
+        .map(|(_, value)| value)
+}
+
+/// The value of the expression in `#{...}` and the format after its colon,
+/// as in `#{N:02}`, or `None` if it uses a variable of a nested invocation.
+/// A format is only accepted when `pasting` the value into an identifier.
+fn evaluate(
+    expr: &Group,
+    bindings: &Bindings,
+    shadowed: &[Ident],
+    pasting: bool,
+) -> Result<Option<(Value, PasteFormat)>> {
+    let tokens: Vec<TokenTree> = expr.stream().into_iter().collect();
+    let colon = tokens
+        .iter()
+        .position(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':'));
+    let (tokens, format) = match colon {
+        Some(colon) if !pasting => {
+            return Err(Error::new(
+                tokens[colon].span(),
+                "a paste format only applies to a value pasted into an identifier",
+            ));
+        }
+        Some(colon) => match &tokens[colon + 1..] {
+            [spec] => (&tokens[..colon], PasteFormat::from_token(spec)?),
+            spec => {
+                let span = spec.first().unwrap_or(&tokens[colon]).span();
+                return Err(Error::new(span, PasteFormat::EXPECTED));
+            }
+        },
+        None => (&tokens[..], PasteFormat::default()),
+    };
+
+    let stream = substitute(tokens.iter().cloned().collect(), bindings, shadowed)?;
+    if mentions(&stream, shadowed) {
+        return Ok(None);
+    }
+    if stream.is_empty() {
+        return Err(Error::new(expr.span(), "expected an expression"));
+    }
+    let value = syn::parse2::<Expr>(stream)?.eval()?;
+    Ok(Some((value, format)))
+}
+
+/// Whether any of `vars` occurs in `tokens`.
+fn mentions(tokens: &TokenStream, vars: &[Ident]) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => vars.contains(&ident),
+        TokenTree::Group(group) => mentions(&group.stream(), vars),
+        _ => false,
+    })
+}
+
+/// `prefix#var`, spanned like the prefix so that errors about the pasted
+/// identifier point at what the caller wrote, while errors about the value
+/// point at `span`.
+fn paste(prefix: &Ident, span: Span, value: &Value, format: &PasteFormat) -> Result<Ident> {
+    let pasted = format!("{}{}", prefix, value.paste(format, span)?);
+    if syn::parse_str::<Ident>(&pasted).is_err() {
+        let msg = format!("`{}` is not a valid identifier", pasted);
+        return Err(Error::new(span, msg));
+    }
+    Ok(Ident::new(&pasted, prefix.span()))
+}
+
+/// The contents of the `#(...)*` section at the start of `tokens`.
+fn section(tokens: &[TokenTree]) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), TokenTree::Punct(star), ..]
+            if pound.as_char() == '#'
+                && group.delimiter() == Delimiter::Parenthesis
+                && star.as_char() == '*' =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+/// The input of the `seq!` or `eseq!` invocation at the start of `tokens`.
+fn nested_seq(tokens: &[TokenTree]) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Ident(name), TokenTree::Punct(bang), TokenTree::Group(group), ..]
+            if (name == "seq" || name == "eseq") && bang.as_char() == '!' =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+/// The variables bound by the input of a nested invocation: `N` in
+/// `N in ...` or `I` and `J` in `(I, J) in ...`.
+fn bound_vars(input: &Group) -> Vec<Ident> {
+    let tokens: Vec<TokenTree> = input.stream().into_iter().collect();
+    match tokens.first() {
+        Some(TokenTree::Ident(var)) => vec![var.clone()],
+        Some(TokenTree::Group(vars)) if vars.delimiter() == Delimiter::Parenthesis => vars
+            .stream()
+            .into_iter()
+            .filter_map(|token| match token {
+                TokenTree::Ident(var) => Some(var),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A copy of `group` with different contents.
+fn with_stream(group: &Group, stream: TokenStream) -> Group {
+    let mut new = Group::new(group.delimiter(), stream);
+    new.set_span(group.span());
+    new
+}
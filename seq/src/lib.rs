@@ -1,10 +1,50 @@
 extern crate proc_macro;
 
+mod eval;
+mod expand;
+mod range;
+mod value;
+
+use crate::expand::Seq;
 use proc_macro::TokenStream;
+use syn::parse_macro_input;
 
+/// Repeats the body once for each value of a range, replacing the loop
+/// variable with the value:
+///
+/// ```ignore
+/// seq!(N in 0..4 {
+///     fn f#N() -> u64 { N * 2 }
+/// });
+/// ```
+///
+/// A pasted value may be formatted, as in `Reg#{N:02}` for zero-padded
+/// digits, `Irq#{N:x}` for hex, or `get_#{C:upper}` to change the case of a
+/// char.
+///
+/// `#{N * 4}` evaluates arithmetic on the loop variables into a literal, and
+/// `next#{N + 1}` pastes the result into an identifier.
+///
+/// If the body contains `#(...)*` sections, only those are repeated.
+///
+/// `(I, J) in 0..4, 0..2` repeats the body for every combination of values,
+/// and invocations may be nested.
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as Seq);
+
+    input
+        .expand()
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
 
-    unimplemented!()
+/// Same as `seq!`, for use in expression position.
+///
+/// The workshop's tests 06, 07 and 10 invoke the macro under this name, which
+/// older compilers needed for macros in a function body. On a current
+/// compiler `seq!` works in expression position too.
+#[proc_macro]
+pub fn eseq(input: TokenStream) -> TokenStream {
+    seq(input)
 }
@@ -0,0 +1,242 @@
+//! The range of values a `seq!` loop variable iterates over.
+
+use crate::eval::Expr;
+use crate::value::{IntFormat, Value};
+use proc_macro2::Span;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, token, Error, Ident, Result, Token};
+
+/// `start..end` or `start..=end`, where the bounds are constant expressions
+/// of integers or single char literals, optionally in parentheses followed
+/// by the adapters `.step_by(n)` and `.rev()`.
+pub struct Range {
+    start: Expr,
+    end: Expr,
+    inclusive: bool,
+    adapters: Vec<Adapter>,
+    /// Where errors about the range as a whole are reported.
+    span: Span,
+}
+
+/// A method applied to the range, as if it were an iterator.
+enum Adapter {
+    /// `.step_by(n)`, where `n` is reported as zero at the span.
+    StepBy(Expr, Span),
+    /// `.rev()`
+    Rev,
+}
+
+impl Parse for Range {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if !input.peek(token::Paren) || !is_parenthesized_range(input) {
+            return parse_bounds(input);
+        }
+
+        let content;
+        parenthesized!(content in input);
+        let mut range = parse_bounds(&content)?;
+        while input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            let method: Ident = input.parse()?;
+            let args;
+            let paren = parenthesized!(args in input);
+            let adapter = if method == "step_by" {
+                Adapter::StepBy(args.parse()?, paren.span)
+            } else if method == "rev" {
+                Adapter::Rev
+            } else {
+                return Err(Error::new(
+                    method.span(),
+                    format!("unsupported range adapter `{}`, expected `step_by` or `rev`", method),
+                ));
+            };
+            if !args.is_empty() {
+                return Err(args.error("unexpected argument"));
+            }
+            range.adapters.push(adapter);
+        }
+        Ok(range)
+    }
+}
+
+/// Whether `input` starts with a whole range in parentheses, rather than with
+/// a bound like `(1 + 2)..8`.
+fn is_parenthesized_range(input: ParseStream) -> bool {
+    let fork = input.fork();
+    let parse = |input: ParseStream| -> Result<()> {
+        let content;
+        parenthesized!(content in input);
+        parse_bounds(&content)?;
+        if content.is_empty() {
+            Ok(())
+        } else {
+            Err(content.error("expected `)`"))
+        }
+    };
+    parse(&fork).is_ok()
+}
+
+fn parse_bounds(input: ParseStream) -> Result<Range> {
+    let span = input.span();
+    let start = input.parse()?;
+    let inclusive = if input.peek(Token![..=]) {
+        input.parse::<Token![..=]>()?;
+        true
+    } else {
+        input.parse::<Token![..]>()?;
+        false
+    };
+    let end = input.parse()?;
+    Ok(Range {
+        start,
+        end,
+        inclusive,
+        adapters: Vec::new(),
+        span,
+    })
+}
+
+/// Most values a range, or all the ranges of an invocation together, may
+/// produce, so that a mistyped bound is reported instead of expanding into
+/// more code than the compiler can hold.
+pub const MAX_VALUES: u128 = 1 << 16;
+
+/// The values of a range as an arithmetic sequence, so that adapters can be
+/// applied without producing every value of the underlying range. The
+/// arithmetic wraps, which gives the exact result whenever a value of the
+/// sequence itself fits in an `i128`.
+struct Progression {
+    first: i128,
+    step: i128,
+    len: u128,
+}
+
+impl Progression {
+    /// `first..=last` counting up by one.
+    fn between(first: i128, last: i128) -> Self {
+        let len = if last < first {
+            0
+        } else {
+            (last.wrapping_sub(first) as u128).saturating_add(1)
+        };
+        Progression { first, step: 1, len }
+    }
+
+    fn step_by(self, step: u128) -> Self {
+        if step >= self.len {
+            return Progression {
+                len: self.len.min(1),
+                ..self
+            };
+        }
+        Progression {
+            first: self.first,
+            step: self.step.wrapping_mul(step as i128),
+            len: self.len.div_ceil(step),
+        }
+    }
+
+    fn rev(self) -> Self {
+        match self.len {
+            0 => self,
+            len => Progression {
+                first: self.nth(len - 1),
+                step: self.step.wrapping_neg(),
+                len,
+            },
+        }
+    }
+
+    fn nth(&self, n: u128) -> i128 {
+        self.first.wrapping_add(self.step.wrapping_mul(n as i128))
+    }
+}
+
+/// What the numbers of a `Progression` stand for.
+enum Kind {
+    Int(IntFormat),
+    /// Chars, numbered by `char_index`.
+    Char,
+}
+
+impl Range {
+    /// Every value in the range, in the order given by its adapters.
+    pub fn values(&self) -> Result<Vec<Value>> {
+        let (kind, mut progression) = self.bounds()?;
+        for adapter in &self.adapters {
+            progression = match adapter {
+                Adapter::StepBy(step, span) => {
+                    let step = match step.eval()? {
+                        Value::Int(step, _) if step > 0 => step,
+                        Value::Int(0, _) => return Err(Error::new(*span, "step must not be zero")),
+                        _ => return Err(Error::new(*span, "step must be a positive integer")),
+                    };
+                    progression.step_by(step as u128)
+                }
+                Adapter::Rev => progression.rev(),
+            };
+        }
+
+        if progression.len > MAX_VALUES {
+            return Err(Error::new(
+                self.span,
+                format!(
+                    "range has more than {} values, which is the most seq! expands",
+                    MAX_VALUES,
+                ),
+            ));
+        }
+        Ok((0..progression.len)
+            .map(|n| {
+                let n = progression.nth(n);
+                match &kind {
+                    Kind::Int(format) => Value::Int(n, format.clone()),
+                    Kind::Char => Value::Char(index_char(n)),
+                }
+            })
+            .collect())
+    }
+
+    /// The values between the bounds, in ascending order.
+    fn bounds(&self) -> Result<(Kind, Progression)> {
+        match (self.start.eval()?, self.end.eval()?) {
+            (Value::Int(start, start_format), Value::Int(end, end_format)) => {
+                let format = start_format.merge(&end_format, self.span)?;
+                let end = if self.inclusive { end } else { end - 1 };
+                if let Some((min, max)) = format.limits() {
+                    if start < min || end > max {
+                        return Err(Error::new(
+                            self.span,
+                            format!(
+                                "range does not fit in `{}`",
+                                format.suffix.as_deref().unwrap_or_default(),
+                            ),
+                        ));
+                    }
+                }
+                Ok((Kind::Int(format), Progression::between(start, end)))
+            }
+            (Value::Char(start), Value::Char(end)) => {
+                let end = char_index(end) - !self.inclusive as i128;
+                Ok((Kind::Char, Progression::between(char_index(start), end)))
+            }
+            _ => Err(Error::new(
+                self.span,
+                "range bounds must both be integers or both be chars",
+            )),
+        }
+    }
+}
+
+/// Position of `c` among all chars, which leaves out the surrogate code
+/// points so that consecutive chars have consecutive positions.
+fn char_index(c: char) -> i128 {
+    let c = c as u32;
+    (if c < 0xD800 { c } else { c - 0x800 }) as i128
+}
+
+/// The char at position `index`, the inverse of `char_index`.
+fn index_char(index: i128) -> char {
+    let index = index as u32;
+    char::from_u32(if index < 0xD800 { index } else { index + 0x800 }).unwrap()
+}
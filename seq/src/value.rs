@@ -0,0 +1,227 @@
+//! Values taken by the loop variable, and how they are written into the
+//! expanded body.
+
+use proc_macro2::{Literal, Span, TokenTree};
+use syn::{Error, Result};
+
+#[derive(Clone)]
+pub enum Value {
+    Int(i128, IntFormat),
+    Char(char),
+}
+
+/// How an integer is written out, taken from the literals of the range so
+/// that `0x00..0x10u8` produces `0x00u8`, `0x01u8`, ...
+#[derive(Clone, Default, PartialEq)]
+pub struct IntFormat {
+    pub radix: Radix,
+    /// Minimum number of digits, after the radix prefix.
+    pub width: usize,
+    pub suffix: Option<String>,
+}
+
+#[derive(Copy, Clone, Default, PartialEq)]
+pub enum Radix {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hex,
+}
+
+impl IntFormat {
+    /// The format of an integer literal as written in the source.
+    pub fn of(repr: &str, suffix: &str) -> Self {
+        let digits = &repr[..repr.len() - suffix.len()];
+        let (radix, digits) = match digits.get(..2) {
+            Some("0b") => (Radix::Binary, &digits[2..]),
+            Some("0o") => (Radix::Octal, &digits[2..]),
+            Some("0x") => (Radix::Hex, &digits[2..]),
+            _ => (Radix::Decimal, digits),
+        };
+        IntFormat {
+            radix,
+            width: digits.chars().filter(|&c| c != '_').count(),
+            suffix: if suffix.is_empty() {
+                None
+            } else {
+                Some(suffix.to_owned())
+            },
+        }
+    }
+
+    /// Format of the result of an operation on integers with formats `self`
+    /// and `other`: the radix and width of the left operand and the suffix
+    /// of whichever has one.
+    pub fn merge(&self, other: &IntFormat, span: Span) -> Result<IntFormat> {
+        let suffix = match (&self.suffix, &other.suffix) {
+            (Some(a), Some(b)) if a != b => {
+                return Err(Error::new(
+                    span,
+                    format!("mismatched integer suffixes `{}` and `{}`", a, b),
+                ));
+            }
+            (Some(suffix), _) | (None, Some(suffix)) => Some(suffix.clone()),
+            (None, None) => None,
+        };
+        Ok(IntFormat {
+            radix: self.radix,
+            width: self.width,
+            suffix,
+        })
+    }
+
+    /// Range of values representable in the type named by the suffix.
+    pub fn limits(&self) -> Option<(i128, i128)> {
+        Some(match self.suffix.as_deref()? {
+            "u8" => (0, u8::MAX as i128),
+            "u16" => (0, u16::MAX as i128),
+            "u32" => (0, u32::MAX as i128),
+            "u64" | "usize" => (0, u64::MAX as i128),
+            "u128" => (0, i128::MAX),
+            "i8" => (i8::MIN as i128, i8::MAX as i128),
+            "i16" => (i16::MIN as i128, i16::MAX as i128),
+            "i32" => (i32::MIN as i128, i32::MAX as i128),
+            "i64" | "isize" => (i64::MIN as i128, i64::MAX as i128),
+            _ => return None,
+        })
+    }
+}
+
+impl Value {
+    /// The value as a literal token.
+    pub fn to_literal(&self, span: Span) -> Literal {
+        let mut literal = match self {
+            Value::Int(n, format) => {
+                let (prefix, digits) = match format.radix {
+                    Radix::Binary => ("0b", format!("{:b}", n.unsigned_abs())),
+                    Radix::Octal => ("0o", format!("{:o}", n.unsigned_abs())),
+                    Radix::Decimal => ("", n.unsigned_abs().to_string()),
+                    Radix::Hex => ("0x", format!("{:x}", n.unsigned_abs())),
+                };
+                let repr = format!(
+                    "{}{}{:0>width$}{}",
+                    if *n < 0 { "-" } else { "" },
+                    prefix,
+                    digits,
+                    format.suffix.as_deref().unwrap_or(""),
+                    width = format.width,
+                );
+                repr.parse().unwrap()
+            }
+            Value::Char(c) => Literal::character(*c),
+        };
+        literal.set_span(span);
+        literal
+    }
+
+    /// The text the value contributes to an identifier it is pasted into.
+    pub fn paste(&self, format: &PasteFormat, span: Span) -> Result<String> {
+        match self {
+            Value::Int(n, _) if *n < 0 => Err(Error::new(
+                span,
+                format!("cannot paste negative value {} into an identifier", n),
+            )),
+            Value::Int(_, _) if format.case.is_some() => Err(Error::new(
+                span,
+                "case conversion can only be applied to chars",
+            )),
+            Value::Int(n, _) => {
+                let width = format.width;
+                Ok(match format.radix {
+                    Radix::Binary => format!("{:0width$b}", n, width = width),
+                    Radix::Octal => format!("{:0width$o}", n, width = width),
+                    Radix::Decimal => format!("{:0width$}", n, width = width),
+                    Radix::Hex if format.uppercase => format!("{:0width$X}", n, width = width),
+                    Radix::Hex => format!("{:0width$x}", n, width = width),
+                })
+            }
+            Value::Char(_) if format.width > 0 || format.radix != Radix::Decimal => Err(
+                Error::new(span, "width and radix can only be applied to integers"),
+            ),
+            Value::Char(c) => Ok(match format.case {
+                Some(Case::Upper) => c.to_uppercase().collect(),
+                Some(Case::Lower) => c.to_lowercase().collect(),
+                None => c.to_string(),
+            }),
+        }
+    }
+}
+
+/// How a value is written when pasted into an identifier, from the spec
+/// after the colon in `prefix#{N:spec}`.
+///
+/// - `02`: decimal, padded with zeros to 2 digits,
+/// - `x`, `X`, `b`, `o`: hex with lowercase or uppercase digits, binary or
+///   octal, optionally after a width as in `04x`,
+/// - `upper`, `lower`: converts the case of a char.
+#[derive(Default)]
+pub struct PasteFormat {
+    width: usize,
+    radix: Radix,
+    /// Hex digits in uppercase.
+    uppercase: bool,
+    case: Option<Case>,
+}
+
+#[derive(Copy, Clone)]
+pub enum Case {
+    Upper,
+    Lower,
+}
+
+impl PasteFormat {
+    /// Error for a spec that is not a paste format.
+    pub const EXPECTED: &'static str = "expected a paste format like `02`, `x`, `04X`, `b` or `upper`";
+
+    /// The format given by the spec `token` after the colon in `#{N:spec}`.
+    pub fn from_token(token: &TokenTree) -> Result<Self> {
+        let format = match token {
+            TokenTree::Ident(ident) => PasteFormat::parse_name(&ident.to_string()),
+            TokenTree::Literal(literal) => PasteFormat::parse_width(&literal.to_string()),
+            _ => None,
+        };
+        format.ok_or_else(|| Error::new(token.span(), PasteFormat::EXPECTED))
+    }
+
+    /// `upper`, `lower` or a radix like `x`.
+    fn parse_name(spec: &str) -> Option<Self> {
+        let mut format = PasteFormat::default();
+        match spec {
+            "upper" => format.case = Some(Case::Upper),
+            "lower" => format.case = Some(Case::Lower),
+            radix if radix.len() == 1 => format.set_radix(radix.chars().next()?)?,
+            _ => return None,
+        }
+        Some(format)
+    }
+
+    /// `02`, `2` or `04x`.
+    fn parse_width(spec: &str) -> Option<Self> {
+        let digits = spec.trim_end_matches(|c: char| !c.is_ascii_digit());
+        let mut format = PasteFormat {
+            width: digits.parse().ok()?,
+            ..PasteFormat::default()
+        };
+        match &spec[digits.len()..] {
+            "" => {}
+            radix if radix.len() == 1 => format.set_radix(radix.chars().next()?)?,
+            _ => return None,
+        }
+        Some(format)
+    }
+
+    fn set_radix(&mut self, radix: char) -> Option<()> {
+        self.radix = match radix {
+            'x' => Radix::Hex,
+            'X' => {
+                self.uppercase = true;
+                Radix::Hex
+            }
+            'b' => Radix::Binary,
+            'o' => Radix::Octal,
+            _ => return None,
+        };
+        Some(())
+    }
+}
@@ -0,0 +1,20 @@
+// Ranges may start below zero. Negative values are substituted as negative
+// literals, so the loop variable can still be used wherever a literal can,
+// including in arguments to `$arg:literal` macro_rules matchers.
+
+use seq::seq;
+
+macro_rules! double {
+    ($n:literal) => {
+        $n * 2
+    };
+}
+
+const VALUES: [i32; 8] = seq!(N in -4..4 { [#(double!(N),)*] });
+
+fn main() {
+    assert_eq!(VALUES, [-8, -6, -4, -2, 0, 2, 4, 6]);
+
+    let sum: i64 = seq!(N in -3..=-1 { 0 #(+ N)* });
+    assert_eq!(sum, -6);
+}
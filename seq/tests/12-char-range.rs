@@ -0,0 +1,29 @@
+// The bounds may also be char literals. The loop variable is substituted as a
+// char literal, and pasting it into an identifier appends the char itself.
+
+use seq::seq;
+
+pub struct Columns {
+    values: [u32; 6],
+}
+
+seq!(C in 'a'..='f' {
+    impl Columns {
+        #(
+            pub fn column_#C(&self) -> u32 {
+                self.values[(C as u8 - b'a') as usize]
+            }
+        )*
+    }
+
+    const NAMES: [char; 6] = [#(C,)*];
+});
+
+fn main() {
+    let columns = Columns {
+        values: [10, 11, 12, 13, 14, 15],
+    };
+    assert_eq!(columns.column_a(), 10);
+    assert_eq!(columns.column_f(), 15);
+    assert_eq!(NAMES, ['a', 'b', 'c', 'd', 'e', 'f']);
+}
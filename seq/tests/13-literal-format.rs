@@ -0,0 +1,22 @@
+// Integer literals in the bounds keep their radix, their number of digits and
+// their suffix in the substituted values. A suffix on either bound applies to
+// every value, and values that do not fit in the suffixed type are an error.
+//
+// Pasting a value into an identifier always uses its decimal digits.
+
+use seq::seq;
+
+const HEX: [&str; 3] = seq!(N in 0x0e..0x11u8 { [#(stringify!(N),)*] });
+const BIN: [&str; 3] = seq!(N in 0b00..=0b10 { [#(stringify!(N),)*] });
+
+seq!(N in 0x08..0x0bu16 {
+    #(
+        const REG_#N: u16 = N;
+    )*
+});
+
+fn main() {
+    assert_eq!(HEX, ["0x0eu8", "0x0fu8", "0x10u8"]);
+    assert_eq!(BIN, ["0b00", "0b01", "0b10"]);
+    assert_eq!(REG_8 + REG_9 + REG_10, 27u16);
+}
@@ -0,0 +1,13 @@
+// Bounds can be written as constant arithmetic on integer literals, with
+// `+ - * / %`, unary minus and parentheses. This is evaluated while expanding
+// the macro, so overflow and division by zero are reported as errors.
+
+use seq::seq;
+
+const SQUARES: [u32; 16] = seq!(N in 0..2*8 { [#(N * N,)*] });
+const NEGATIVE: [i8; 3] = seq!(N in -(2 * 2) + 1..(10 - 1) % 4 - 1 { [#(N,)*] });
+
+fn main() {
+    assert_eq!(SQUARES[15], 225);
+    assert_eq!(NEGATIVE, [-3, -2, -1]);
+}
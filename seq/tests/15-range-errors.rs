@@ -0,0 +1,16 @@
+// Mistakes in the bounds are reported while expanding the macro, pointing at
+// the part of the range responsible.
+
+use seq::seq;
+
+seq!(N in 0..=256u8 {});
+
+seq!(N in 0..0x10 / (4 - 4) {});
+
+seq!(N in 'a'..10 {});
+
+seq!(N in -2..2 {
+    fn f#N() {}
+});
+
+fn main() {}
@@ -0,0 +1,32 @@
+// A range in parentheses may be followed by `.step_by(n)` and `.rev()`, which
+// behave like the iterator methods of the same name and can be chained in any
+// order. The step may be a constant expression like the bounds.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(16) {
+    #(
+        pub const OFFSET_#N: usize = N;
+    )*
+});
+
+const DESCENDING: [u8; 8] = seq!(N in (0..8).rev() { [#(N,)*] });
+const EVEN_DOWN: [u8; 3] = seq!(N in (0..=10).step_by(2 * 2).rev() { [#(N,)*] });
+const DOWN_EVEN: [u8; 4] = seq!(N in (0..=10).rev().step_by(3) { [#(N,)*] });
+
+// Stepping through a large range only produces the values stepped to.
+const BILLIONS: [u64; 2] = seq!(N in (0..2_000_000_000u64).step_by(1_000_000_000) { [#(N,)*] });
+const BILLIONS_DOWN: [u64; 3] = seq!(N in (0..=4_000_000_000u64).rev().step_by(2_000_000_000) { [#(N,)*] });
+
+// A parenthesized bound is still just a bound.
+const SHIFTED: [u8; 2] = seq!(N in (1 + 1)..4 { [#(N,)*] });
+
+fn main() {
+    assert_eq!(OFFSET_0 + OFFSET_16 + OFFSET_32 + OFFSET_48, 96);
+    assert_eq!(DESCENDING, [7, 6, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(EVEN_DOWN, [8, 4, 0]);
+    assert_eq!(DOWN_EVEN, [10, 7, 4, 1]);
+    assert_eq!(SHIFTED, [2, 3]);
+    assert_eq!(BILLIONS, [0, 1_000_000_000]);
+    assert_eq!(BILLIONS_DOWN, [4_000_000_000, 2_000_000_000, 0]);
+}
@@ -0,0 +1,13 @@
+// A step of zero would never make progress, and only `step_by` and `rev` are
+// understood after a range. A range may not expand to more than 65536 values,
+// however many the underlying range has before stepping.
+
+use seq::seq;
+
+seq!(N in (0..8).step_by(4 - 4) {});
+
+seq!(N in (0..8).skip(2) {});
+
+seq!(N in (0..2_000_000_000).step_by(1000) {});
+
+fn main() {}
@@ -0,0 +1,42 @@
+// A seq! invocation may contain another one. The outer invocation substitutes
+// its variable inside the inner one, including in its bounds, but leaves the
+// inner `#(...)*` sections for the inner invocation to repeat. Pasting works
+// across both: `m#I#J` becomes `m0#J` in the outer expansion and `m00` in the
+// inner one.
+//
+// An inner invocation binding a variable of the same name shadows the outer
+// one.
+
+use seq::seq;
+
+pub struct Matrix([[u8; 3]; 3]);
+
+seq!(I in 0..3 {
+    impl Matrix {
+        seq!(J in 0..3 {
+            #(
+                pub fn m#I#J(&self) -> u8 {
+                    self.0[I][J]
+                }
+            )*
+        });
+    }
+});
+
+// The bounds of the inner range may depend on the outer variable.
+// This sums 0 + (0 + 1) + (0 + 1 + 2) + (0 + 1 + 2 + 3).
+const TRIANGLE: u8 = seq!(I in 0..4 {
+    0 #(+ seq!(J in 0..=I { 0 #(+ J)* }))*
+});
+
+const SHADOWED: [u8; 2] = seq!(N in 5..7 { [#(seq!(N in 0..1 { N }) + N,)*] });
+
+fn main() {
+    let matrix = Matrix([[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+    assert_eq!(matrix.m00(), 0);
+    assert_eq!(matrix.m12(), 5);
+    assert_eq!(matrix.m21(), 7);
+
+    assert_eq!(TRIANGLE, 10);
+    assert_eq!(SHADOWED, [5, 6]);
+}
@@ -0,0 +1,28 @@
+// Several variables can be bound at once, each with its own range, to iterate
+// over every combination of their values without nesting invocations. The
+// last variable varies fastest.
+
+use seq::seq;
+
+pub struct Matrix([[u8; 2]; 3]);
+
+impl Matrix {
+    seq!((I, J) in 0..3, 0..2 {
+        #(
+            pub fn m#I#J(&self) -> u8 {
+                self.0[I][J]
+            }
+        )*
+    });
+}
+
+const PAIRS: [(u8, char); 4] = seq!((N, C) in (0..2).rev(), 'x'..='y' { [#((N, C),)*] });
+
+fn main() {
+    let matrix = Matrix([[0, 1], [2, 3], [4, 5]]);
+    assert_eq!(matrix.m00(), 0);
+    assert_eq!(matrix.m11(), 3);
+    assert_eq!(matrix.m21(), 5);
+
+    assert_eq!(PAIRS, [(1, 'x'), (1, 'y'), (0, 'x'), (0, 'y')]);
+}
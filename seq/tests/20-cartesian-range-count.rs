@@ -0,0 +1,10 @@
+// Every variable needs its own range, and all the combinations of their values
+// together are limited to 65536, like the values of a single range.
+
+use seq::seq;
+
+seq!((I, J) in 0..4 {});
+
+seq!((I, J) in 0..300, 0..300 {});
+
+fn main() {}
@@ -0,0 +1,56 @@
+// A pasted value may be given a format spec, `prefix#{N:spec}`, so that
+// generated names sort well and match the names used by hardware manuals:
+//
+//     - `02` pads with zeros to two digits: `Reg#{N:02}` -> Reg00 .. Reg15,
+//     - `x`, `X`, `b` and `o` write the value in hex, binary or octal,
+//       optionally after a width as in `04X`,
+//     - `upper` and `lower` convert the case of a char.
+//
+// The spec goes inside the braces, so a colon after `prefix#N` is left
+// alone, as in `let x#N: u8`.
+
+use seq::seq;
+
+seq!(N in 0..16 {
+    #[allow(non_upper_case_globals)]
+    pub mod regs {
+        #(
+            pub const Reg#{N:02}: u32 = N;
+            pub const Irq#{N:x}: u32 = N;
+            pub const Mask#{N:04X}: u32 = N;
+            pub const Bit#{N:b}: u32 = N;
+        )*
+    }
+});
+
+seq!(C in 'a'..='c' {
+    #(
+        #[allow(non_snake_case)]
+        fn get_#{C:upper}() -> char {
+            C
+        }
+    )*
+});
+
+seq!(C in 'A'..='C' {
+    #(
+        #[allow(non_snake_case)]
+        fn set_#{C:lower}() -> char {
+            let value#C: char = C;
+            value#C
+        }
+    )*
+});
+
+fn main() {
+    assert_eq!(regs::Reg00, 0);
+    assert_eq!(regs::Reg07, 7);
+    assert_eq!(regs::Reg15, 15);
+    assert_eq!(regs::Irqa, 10);
+    assert_eq!(regs::Irqf, 15);
+    assert_eq!(regs::Mask000B, 11);
+    assert_eq!(regs::Bit101, 5);
+    assert_eq!(get_A(), 'a');
+    assert_eq!(get_C(), 'c');
+    assert_eq!(set_b(), 'B');
+}
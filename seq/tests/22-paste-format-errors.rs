@@ -0,0 +1,23 @@
+// Paste formats that do not apply to the value, or that cannot be parsed, are
+// reported at the pasted value or the spec. A format on a value that is not
+// pasted into an identifier is an error too.
+
+use seq::seq;
+
+seq!(N in 0..2 {
+    fn f#{N:upper}() {}
+});
+
+seq!(C in 'a'..'c' {
+    fn g#{C:02}() {}
+});
+
+seq!(N in 0..2 {
+    fn h#{N:2q}() {}
+});
+
+seq!(N in 0..2 {
+    const _: u8 = #{N:02};
+});
+
+fn main() {}
@@ -0,0 +1,79 @@
+// Within the body, `#{...}` is an expression over the loop variables,
+// evaluated while expanding the macro. On its own it becomes an integer
+// literal, and after an identifier it is pasted like a variable, so that each
+// field can refer to its offset `#{N * 4}` or to the next field
+// `next#{N + 1}`.
+//
+// Braces keep it apart from a `#(...)*` section, so an expression may be
+// followed by `*` as in `#{N * 4} * 2`.
+//
+// The literal keeps the radix and suffix of the values it was computed from,
+// and pasted expressions accept the same formats as pasted variables.
+
+use seq::seq;
+
+pub struct Regs;
+
+seq!(N in 0..4 {
+    impl Regs {
+        #(
+            pub const OFFSET_#N: usize = #{N * 4};
+            pub const NEXT_#N: usize = #{N + 1};
+        )*
+    }
+
+    #(
+        fn step#N() -> i32 {
+            #{N % 2 + -(1 - N) * 10}
+        }
+
+        #[allow(dead_code)]
+        fn link#N() -> &'static str {
+            stringify!(link#{N + 1})
+        }
+    )*
+});
+
+seq!(N in 0..3 {
+    fn reg#{N + 14:02}() -> u8 {
+        #{N + 14}
+    }
+});
+
+seq!(N in 0x10u8..0x12 {
+    const MASKS: [u8; 2] = [#(#{N * 2},)*];
+});
+
+fn doubled_offsets() -> Vec<usize> {
+    let mut v = Vec::new();
+    seq!(N in 0..3 {
+        #(
+            v.push(#{N * 4} * 2);
+        )*
+    });
+    v
+}
+
+seq!(I in 0..2 {
+    seq!(J in 0..2 {
+        #[allow(dead_code)]
+        fn cell#I#J() -> u32 {
+            #{I * 2 + J}
+        }
+    });
+});
+
+fn main() {
+    assert_eq!(Regs::OFFSET_0, 0);
+    assert_eq!(Regs::OFFSET_3, 12);
+    assert_eq!(Regs::NEXT_3, 4);
+    assert_eq!(step0(), -10);
+    assert_eq!(step1(), 1);
+    assert_eq!(step3(), 21);
+    assert_eq!(link2(), "link3");
+    assert_eq!(reg14() + reg16(), 30);
+    assert_eq!(MASKS, [0x20, 0x22]);
+    assert_eq!(doubled_offsets(), [0, 8, 16]);
+    assert_eq!(cell10(), 2);
+    assert_eq!(cell11(), 3);
+}
@@ -0,0 +1,23 @@
+// Expressions in the body are evaluated with checked arithmetic in the type
+// named by the suffix of the range, so overflow is reported at the operator
+// rather than producing a literal that does not fit.
+
+use seq::seq;
+
+seq!(N in 250u8..=255 {
+    const _: u8 = #{N + 10};
+});
+
+seq!(N in 0..2 {
+    const _: i32 = #{N / (N - N)};
+});
+
+seq!(N in 0..2 {
+    const _: i32 = #{N * M};
+});
+
+seq!(N in 0..2 {
+    fn f#{N - 1}() {}
+});
+
+fn main() {}
@@ -0,0 +1,31 @@
+// A colon after a pasted identifier belongs to the surrounding code, so that
+// pasted fields can be declared with any type, including a type parameter
+// named like a paste format, and initialized in a struct literal.
+
+use seq::seq;
+
+seq!(N in 0..3 {
+    struct Counts {
+        #( f#N: u8, )*
+    }
+
+    struct Pair<X> {
+        #( f#N: X, )*
+    }
+
+    fn counts() -> Counts {
+        Counts { #( f#N: 1, )* }
+    }
+
+    fn pair<X: Copy>(x: X) -> Pair<X> {
+        Pair { #( f#N: x, )* }
+    }
+});
+
+fn main() {
+    let counts = counts();
+    assert_eq!(counts.f0 + counts.f1 + counts.f2, 3);
+
+    let pair = pair('x');
+    assert_eq!([pair.f0, pair.f1, pair.f2], ['x'; 3]);
+}
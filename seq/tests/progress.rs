@@ -1,14 +1,29 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse-header.rs");
-    //t.pass("tests/02-parse-body.rs");
-    //t.compile_fail("tests/03-expand-four-errors.rs");
-    //t.pass("tests/04-paste-ident.rs");
-    //t.pass("tests/05-repeat-section.rs");
-    //t.pass("tests/06-make-work-in-function.rs");
-    //t.pass("tests/07-init-array.rs");
-    //t.pass("tests/08-inclusive-range.rs");
-    //t.compile_fail("tests/09-ident-span.rs");
-    //t.pass("tests/10-interaction-with-macrorules.rs");
+    t.pass("tests/01-parse-header.rs");
+    t.pass("tests/02-parse-body.rs");
+    t.compile_fail("tests/03-expand-four-errors.rs");
+    t.pass("tests/04-paste-ident.rs");
+    t.pass("tests/05-repeat-section.rs");
+    t.pass("tests/06-make-work-in-function.rs");
+    t.pass("tests/07-init-array.rs");
+    t.pass("tests/08-inclusive-range.rs");
+    t.compile_fail("tests/09-ident-span.rs");
+    t.pass("tests/10-interaction-with-macrorules.rs");
+    t.pass("tests/11-negative-range.rs");
+    t.pass("tests/12-char-range.rs");
+    t.pass("tests/13-literal-format.rs");
+    t.pass("tests/14-const-bounds.rs");
+    t.compile_fail("tests/15-range-errors.rs");
+    t.pass("tests/16-step-and-rev.rs");
+    t.compile_fail("tests/17-range-adapter-errors.rs");
+    t.pass("tests/18-nested.rs");
+    t.pass("tests/19-cartesian.rs");
+    t.compile_fail("tests/20-cartesian-range-count.rs");
+    t.pass("tests/21-formatted-paste.rs");
+    t.compile_fail("tests/22-paste-format-errors.rs");
+    t.pass("tests/23-index-arithmetic.rs");
+    t.compile_fail("tests/24-index-arithmetic-errors.rs");
+    t.pass("tests/25-paste-before-colon.rs");
 }